@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use reqwest::{Client, Request, RequestBuilder, Response};
+
+use crate::{ClientConfig, NanoError};
+
+/// Hook called before every request is sent, implement it to add custom authentication
+/// like short lived tokens or HMAC request signatures
+///
+/// ## Example
+/// ```ignore
+/// struct BearerToken(TokenCache);
+///
+/// #[async_trait]
+/// impl AuthProvider for BearerToken {
+///     async fn authorize(&self, request: &mut Request) -> Result<(), NanoError> {
+///         let token = self.0.current_or_refresh().await?;
+///         request
+///             .headers_mut()
+///             .insert(AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+///         Ok(())
+///     }
+/// }
+///
+/// let nano = Nano::builder("https://couchdb.example.com")
+///                 .auth_provider(BearerToken(TokenCache::new()))
+///                 .build()?;
+/// ```
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// Add the headers or otherwise change the request before it is sent,
+    /// method, url, headers and body are final at this point
+    async fn authorize(&self, request: &mut Request) -> Result<(), NanoError>;
+}
+
+impl std::fmt::Debug for dyn AuthProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "AuthProvider")
+    }
+}
+
+/// Send requests through the [AuthProvider] of the client settings
+pub(crate) trait SendExt {
+    async fn send_with(self, client: &Client, config: &ClientConfig)
+        -> Result<Response, NanoError>;
+}
+
+impl SendExt for RequestBuilder {
+    async fn send_with(
+        self,
+        client: &Client,
+        config: &ClientConfig,
+    ) -> Result<Response, NanoError> {
+        let mut request = self.build()?;
+        if let Some(auth) = &config.auth {
+            auth.authorize(&mut request).await?;
+        }
+        #[cfg(feature = "tracing")]
+        let (method, url, start) = (
+            request.method().clone(),
+            crate::error::redact_url(request.url().as_str()),
+            std::time::Instant::now(),
+        );
+        let response = client.execute(request).await;
+        #[cfg(feature = "tracing")]
+        match &response {
+            Ok(response) => tracing::debug!(
+                %method,
+                %url,
+                status = response.status().as_u16(),
+                elapsed = ?start.elapsed(),
+                "CouchDB request"
+            ),
+            Err(err) => tracing::debug!(%method, %url, %err, "CouchDB request failed"),
+        }
+        Ok(response?)
+    }
+}
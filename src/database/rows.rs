@@ -0,0 +1,251 @@
+/// Splits the body of an `_all_docs` or view response, received in chunks, into the raw JSON of its rows
+///
+/// Only the row being received is buffered, so a response can be processed row by row whatever its size.
+/// The body is scanned once tracking the nesting depth and the strings, a row is an object directly inside
+/// the `rows` array of the top level object.
+#[derive(Debug, Default)]
+pub(crate) struct RowSplitter {
+    /// Bytes of the row being received
+    row: Vec<u8>,
+    /// Nesting depth of the current byte
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// Current string of the top level object
+    string: Vec<u8>,
+    /// Last completed string of the top level object, the key of the following value
+    key: Vec<u8>,
+    in_rows: bool,
+    in_row: bool,
+    /// The `rows` array has been closed
+    done: bool,
+}
+
+impl RowSplitter {
+    /// Feed the next chunk of the body, returning the rows completed by it
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        let mut rows = vec![];
+        for &byte in chunk {
+            if self.in_row {
+                self.row.push(byte);
+            }
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                    if self.depth == 1 {
+                        self.key = std::mem::take(&mut self.string);
+                    }
+                } else if self.depth == 1 {
+                    self.string.push(byte);
+                }
+                continue;
+            }
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+                    self.string.clear();
+                }
+                b'{' | b'[' => {
+                    if byte == b'[' && self.depth == 1 && self.key == b"rows" {
+                        self.in_rows = true;
+                    } else if byte == b'{' && self.in_rows && self.depth == 2 {
+                        self.in_row = true;
+                        self.row.clear();
+                        self.row.push(byte);
+                    }
+                    self.depth += 1;
+                }
+                b'}' | b']' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    if self.in_row && self.depth == 2 {
+                        self.in_row = false;
+                        rows.push(std::mem::take(&mut self.row));
+                    } else if self.in_rows && self.depth == 1 {
+                        self.in_rows = false;
+                        self.done = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        rows
+    }
+
+    /// Whether the whole `rows` array has been received
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+/// Splits a body received in chunks into its lines, e.g. the changes of a continuous `_changes` feed
+///
+/// A line split across chunks, even inside a multi-byte character, is returned once its end is received.
+#[derive(Debug, Default)]
+pub(crate) struct LineSplitter {
+    /// Bytes of the line being received
+    pending: Vec<u8>,
+}
+
+impl LineSplitter {
+    /// Feed the next chunk of the body, returning the non empty lines completed by it without their terminator
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        self.pending.extend_from_slice(chunk);
+        let mut lines = vec![];
+        let mut start = 0;
+        while let Some(end) = self.pending[start..].iter().position(|&byte| byte == b'\n') {
+            let line = self.pending[start..start + end].trim_ascii();
+            if !line.is_empty() {
+                lines.push(line.to_vec());
+            }
+            start += end + 1;
+        }
+        self.pending.drain(..start);
+        lines
+    }
+
+    /// Line left without terminator at the end of the body
+    pub(crate) fn finish(&mut self) -> Option<Vec<u8>> {
+        let line = std::mem::take(&mut self.pending);
+        let line = line.trim_ascii();
+        (!line.is_empty()).then(|| line.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{LineSplitter, RowSplitter};
+
+    /// Feed `body` split in chunks of `size` bytes and parse the returned rows
+    fn split(body: &str, size: usize) -> (Vec<Value>, bool) {
+        let mut splitter = RowSplitter::default();
+        let rows = body
+            .as_bytes()
+            .chunks(size)
+            .flat_map(|chunk| splitter.push(chunk))
+            .map(|row| serde_json::from_slice(&row).unwrap())
+            .collect();
+        (rows, splitter.is_done())
+    }
+
+    /// Every chunk size yields the same rows, so every byte of the body falls once on a chunk boundary
+    fn assert_rows(body: &str, expected: &[Value]) {
+        for size in 1..=body.len() {
+            let (rows, done) = split(body, size);
+            assert_eq!(rows, expected, "chunk size {}", size);
+            assert!(done, "chunk size {}", size);
+        }
+    }
+
+    #[test]
+    fn row_split_mid_string() {
+        let body = r#"{"total_rows":2,"offset":0,"rows":[{"id":"a","key":"a","value":{"rev":"1-x"}},{"id":"caffè","key":"caffè","value":{"rev":"2-y"}}]}"#;
+        assert_rows(
+            body,
+            &[
+                json!({"id": "a", "key": "a", "value": {"rev": "1-x"}}),
+                json!({"id": "caffè", "key": "caffè", "value": {"rev": "2-y"}}),
+            ],
+        );
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        let body = r#"{"rows":[{"id":"a","doc":{"quote":"she said \"hi\"","backslash":"c:\\dir\\","both":"\\\""}}]}"#;
+        assert_rows(
+            body,
+            &[
+                json!({"id": "a", "doc": {"quote": "she said \"hi\"", "backslash": "c:\\dir\\", "both": "\\\""}}),
+            ],
+        );
+    }
+
+    #[test]
+    fn braces_inside_strings() {
+        let body = r#"{"rows":[{"id":"{a}","doc":{"text":"} ] { [","rows":"[{\"id\":1}]"}},{"id":"b","key":"]"}]}"#;
+        assert_rows(
+            body,
+            &[
+                json!({"id": "{a}", "doc": {"text": "} ] { [", "rows": "[{\"id\":1}]"}}),
+                json!({"id": "b", "key": "]"}),
+            ],
+        );
+    }
+
+    #[test]
+    fn nested_objects() {
+        let body = r#"{"total_rows":1,"rows":[{"id":"a","doc":{"nested":{"deep":{"list":[{"x":1},[2,{"y":[3]}]]}},"rows":[{"id":"inner"}]}}],"offset":0}"#;
+        assert_rows(
+            body,
+            &[
+                json!({"id": "a", "doc": {"nested": {"deep": {"list": [{"x": 1}, [2, {"y": [3]}]]}}, "rows": [{"id": "inner"}]}}),
+            ],
+        );
+    }
+
+    #[test]
+    fn rows_key_after_other_fields() {
+        let body = r#"{"offset":0,"meta":{"rows":[{"id":"not a row"}]},"rows":[{"id":"a"}]}"#;
+        assert_rows(body, &[json!({"id": "a"})]);
+    }
+
+    /// Feed `body` split in chunks of `size` bytes, returning the lines and the unterminated last one
+    fn lines(body: &str, size: usize) -> (Vec<String>, Option<String>) {
+        let mut splitter = LineSplitter::default();
+        let lines = body
+            .as_bytes()
+            .chunks(size)
+            .flat_map(|chunk| splitter.push(chunk))
+            .map(|line| String::from_utf8(line).unwrap())
+            .collect();
+        let last = splitter
+            .finish()
+            .map(|line| String::from_utf8(line).unwrap());
+        (lines, last)
+    }
+
+    #[test]
+    fn lines_split_across_chunks() {
+        let body = "{\"seq\":\"1-a\",\"id\":\"caffè\"}\n{\"seq\":\"2-b\",\"id\":\"b\"}\n";
+        for size in 1..=body.len() {
+            let (lines, last) = lines(body, size);
+            assert_eq!(
+                lines,
+                [r#"{"seq":"1-a","id":"caffè"}"#, r#"{"seq":"2-b","id":"b"}"#],
+                "chunk size {}",
+                size
+            );
+            assert_eq!(last, None, "chunk size {}", size);
+        }
+    }
+
+    #[test]
+    fn heartbeat_lines_are_skipped() {
+        let body = "\n\n{\"id\":\"a\"}\r\n  \n\n";
+        for size in 1..=body.len() {
+            let (lines, last) = lines(body, size);
+            assert_eq!(lines, [r#"{"id":"a"}"#], "chunk size {}", size);
+            assert_eq!(last, None, "chunk size {}", size);
+        }
+    }
+
+    #[test]
+    fn unterminated_last_line() {
+        let body = "{\"id\":\"a\"}\n{\"last_seq\":\"3-c\",\"pending\":0}";
+        for size in 1..=body.len() {
+            let (lines, last) = lines(body, size);
+            assert_eq!(lines, [r#"{"id":"a"}"#], "chunk size {}", size);
+            assert_eq!(
+                last.as_deref(),
+                Some(r#"{"last_seq":"3-c","pending":0}"#),
+                "chunk size {}",
+                size
+            );
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// Attachment written to a writer by `download_attachment_to`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttachmentDownload {
+    /// Number of bytes written
+    pub bytes: u64,
+    /// MD5 digest of the written content, in the `md5-<base64>` form used by attachment stubs
+    pub digest: String,
+    /// Attachment MIME type, from the `Content-Type` header
+    pub content_type: Option<String>,
+    /// `true` if CouchDB sent the digest of the attachment and it matched the written content
+    pub verified: bool,
+}
+
+/// Attachment stored together with its document by `put_doc_with_attachments`
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    /// Attachment name
+    name: String,
+    /// Attachment MIME type
+    content_type: String,
+    /// Attachment content
+    data: Bytes,
+}
+
+impl Attachment {
+    pub fn new<A, B, C>(name: A, content_type: B, data: C) -> Self
+    where
+        A: Into<String>,
+        B: Into<String>,
+        C: Into<Bytes>,
+    {
+        Self {
+            name: name.into(),
+            content_type: content_type.into(),
+            data: data.into(),
+        }
+    }
+
+    /// Attachment name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Attachment MIME type
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// Attachment content
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+}
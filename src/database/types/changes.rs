@@ -1,10 +1,12 @@
 use std::borrow::Borrow;
+use std::time::Duration;
 
-use bevy_reflect::Reflect;
+#[cfg(feature = "reflect")]
+use bevy_reflect::{FromReflect, Reflect};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::MangoQuery;
+use super::{option_string_or_number, string_or_number, MangoQuery};
 
 /// Returns a sorted list of changes made to documents in the database, in time order of application, can be obtained from the database’s `_changes` resource.
 ///
@@ -20,6 +22,7 @@ pub struct ChangesResponse {
     /// A vector of changes made to a database
     pub results: Option<Vec<ChangesDoc>>,
     /// Last change update sequence
+    #[serde(default, deserialize_with = "option_string_or_number")]
     pub last_seq: Option<String>,
     // Count of remaining items in the feed
     pub pending: Option<i64>,
@@ -27,6 +30,7 @@ pub struct ChangesResponse {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChangesDoc {
     /// Update sequence
+    #[serde(deserialize_with = "string_or_number")]
     pub seq: String,
     ///  Document ID
     pub id: String,
@@ -37,6 +41,54 @@ pub struct ChangesDoc {
     /// include doc body if `include_doc=true` is provided
     pub doc: Option<Value>,
 }
+/// Event recorded in the `_global_changes` database for every database of the node
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DbEvent {
+    /// The database has been created
+    Created,
+    /// Documents of the database have been written
+    Updated,
+    /// The database has been deleted
+    Deleted,
+    /// A design document of the database has been written
+    DdocUpdated,
+    /// An event unknown to this client
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl DbEvent {
+    /// Split the ID of a `_global_changes` document, formatted as `event:db-name`, into the database
+    /// name and its event
+    pub fn parse_doc_id(id: &str) -> Option<(String, DbEvent)> {
+        let (event, db_name) = id.split_once(':')?;
+        if event.is_empty() || db_name.is_empty() {
+            return None;
+        }
+        let event = match event {
+            "created" => DbEvent::Created,
+            "updated" => DbEvent::Updated,
+            "deleted" => DbEvent::Deleted,
+            "ddoc_updated" => DbEvent::DdocUpdated,
+            other => DbEvent::Other(other.to_string()),
+        };
+        Some((db_name.to_string(), event))
+    }
+}
+
+impl std::fmt::Display for DbEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DbEvent::Created => write!(f, "created"),
+            DbEvent::Updated => write!(f, "updated"),
+            DbEvent::Deleted => write!(f, "deleted"),
+            DbEvent::DdocUpdated => write!(f, "ddoc_updated"),
+            DbEvent::Other(event) => write!(f, "{event}"),
+        }
+    }
+}
+
 /// Document leaves with single field `rev`
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Changes {
@@ -44,22 +96,23 @@ pub struct Changes {
     pub rev: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
 pub struct ChangesQueryParamsStream {
     /// Includes conflicts information in response. Ignored if isn’t `true`
     conflicts: bool,
     ///  Return the change results in descending sequence order (most recent change first). Default is `false`.
     descending: bool,
     /// `normal` Specifies Normal Polling Mode. All past changes are returned immediately. Default.
-    feed: String,
+    feed: Feed,
     /// Reference to a filter function from a design document that will filter whole stream emitting only filtered events.
-    filter: String,
+    filter: Option<Filter>,
     /// Period in milliseconds after which an empty line is sent in the results.
     ///
     /// Only applicable for `longpoll`, `continuous`, and `eventsource` feeds. Overrides any timeout to keep the feed alive indefinitely.
     ///
     /// Default is `60000`
-    heartbeat: i64,
+    heartbeat: Option<i64>,
     /// Include the associated document with each result. If there are conflicts, only the winning revision is returned. Default is `false`
     include_docs: bool,
     /// Include the Base64-encoded content of attachments in the documents that are included if `include_docs` is `true`.
@@ -71,33 +124,40 @@ pub struct ChangesQueryParamsStream {
     /// Ignored if `include_docs` isn’t `true`. Default is `false`.
     att_encoding_info: bool,
     /// Limit number of result rows to the specified value (note that using 0 here has the same effect as 1).
-    limit: i64,
+    limit: Option<i64>,
     /// Specifies how many revisions are returned in the changes array. The default, `main_only`, will only return the current “winning” revision;
     ///
     /// `all_docs` will return all leaf revisions (including conflicts and deleted former conflicts).
-    style: String,
+    style: Option<Style>,
     ///  Maximum period in milliseconds to wait for a change before the response is sent, even if there are no results.
     ///
     /// Only applicable for `longpoll` or `continuous` feeds. Default value is specified by `chttpd/changes_timeout` configuration option.
     ///
     ///  Note that `60000` value is also the default maximum timeout to prevent undetected dead connections.
-    timeout: i64,
+    timeout: Option<i64>,
     /// Allows to use view functions as filters. Documents counted as “passed” for view filter in case if map function emits at least one record for them.
     view: String,
     /// When fetching changes in a batch, setting the seq_interval parameter tells CouchDB to only calculate the update seq with every Nth result returned.
     ///
     /// By setting `seq_interval=<batch size>` , where `<batch size>` is the number of results requested per batch, load can be reduced on the source CouchDB database;
     /// computing the seq value across many shards (esp. in highly-sharded databases) is expensive in a heavily loaded CouchDB cluster.
-    seq_interval: i64,
+    seq_interval: Option<i64>,
+    /// Start the results from the change immediately after the given update sequence, `now` starts from the current one.
+    since: Option<String>,
+    /// Maximum silence on the connection before it is considered dead and reopened, not sent to CouchDB
+    #[cfg_attr(feature = "reflect", reflect(ignore))]
+    #[serde(skip)]
+    inactivity_timeout: Option<Duration>,
 }
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
 pub struct ChangesQueryParams {
     /// Includes conflicts information in response. Ignored if isn’t `true`
     conflicts: bool,
     ///  Return the change results in descending sequence order (most recent change first). Default is `false`.
     descending: bool,
     /// Reference to a filter function from a design document that will filter whole stream emitting only filtered events.
-    filter: String,
+    filter: Option<Filter>,
     /// Include the associated document with each result. If there are conflicts, only the winning revision is returned. Default is `false`
     include_docs: bool,
     /// Include the Base64-encoded content of attachments in the documents that are included if `include_docs` is `true`.
@@ -109,24 +169,29 @@ pub struct ChangesQueryParams {
     /// Ignored if `include_docs` isn’t `true`. Default is `false`.
     att_encoding_info: bool,
     /// Limit number of result rows to the specified value (note that using 0 here has the same effect as 1).
-    limit: i64,
+    limit: Option<i64>,
     /// Specifies how many revisions are returned in the changes array. The default, `main_only`, will only return the current “winning” revision;
     ///
     /// `all_docs` will return all leaf revisions (including conflicts and deleted former conflicts).
-    style: String,
+    style: Option<Style>,
     /// Allows to use view functions as filters. Documents counted as “passed” for view filter in case if map function emits at least one record for them.
     view: String,
     /// When fetching changes in a batch, setting the seq_interval parameter tells CouchDB to only calculate the update seq with every Nth result returned.
     ///
     /// By setting `seq_interval=<batch size>` , where `<batch size>` is the number of results requested per batch, load can be reduced on the source CouchDB database;
     /// computing the seq value across many shards (esp. in highly-sharded databases) is expensive in a heavily loaded CouchDB cluster.
-    seq_interval: i64,
+    seq_interval: Option<i64>,
+    /// Start the results from the change immediately after the given update sequence, `now` starts from the current one.
+    since: Option<String>,
 }
 
 /// Feed options
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
+#[serde(rename_all = "lowercase")]
 pub enum Feed {
     /// Equal to a regualr Request/Response
+    #[default]
     Normal,
     LongPoll,
     /// A continuous connection in a Stream fashion between CouchDB and the client
@@ -145,14 +210,11 @@ impl std::fmt::Display for Feed {
     }
 }
 
-impl Default for Feed {
-    fn default() -> Self {
-        Self::Normal
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
+#[serde(rename_all = "snake_case")]
 pub enum Style {
+    #[default]
     MainOnly,
     AllDocs,
 }
@@ -166,21 +228,19 @@ impl std::fmt::Display for Style {
     }
 }
 
-impl Default for Style {
-    fn default() -> Self {
-        Self::MainOnly
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub enum Filter {
     /// `filter=_selector`
     ///
     /// This filter accepts only changes for documents which match a specified selector, defined using the same selector syntax used for `_find`.
+    #[serde(rename = "_selector")]
     Selector,
     /// This filter accepts only changes for documents which ID in specified in doc_ids query parameter or payload’s object array
+    #[serde(rename = "_doc_ids")]
     DocIds,
     /// The `_design` filter accepts only changes for any design document within the requested database.
+    #[serde(rename = "_design")]
     Design,
 }
 
@@ -194,12 +254,14 @@ impl std::fmt::Display for Filter {
     }
 }
 
+// built once per request and passed by reference, boxing the query would only add an allocation
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum ChangesQueryData<'a> {
     /// Selector json used to make a query, it can accept either `serde_json::json!()` or `MangoQuery` type
     ///
     /// ### Example of a selector example
-    /// ```
+    /// ```json
     /// {
     ///    "selector": { "_id": { "$regex": "^_design/" } }
     /// }
@@ -216,14 +278,16 @@ impl Default for ChangesQueryParamsStream {
             attachments: bool::default(),
             conflicts: bool::default(),
             descending: bool::default(),
-            feed: Feed::Continuous.to_string(),
-            filter: String::default(),
-            heartbeat: i64::default(),
+            feed: Feed::Continuous,
+            filter: Option::default(),
+            heartbeat: Option::default(),
             include_docs: bool::default(),
-            limit: i64::default(),
-            seq_interval: i64::default(),
-            style: String::default(),
-            timeout: i64::default(),
+            inactivity_timeout: Option::default(),
+            limit: Option::default(),
+            seq_interval: Option::default(),
+            since: Option::default(),
+            style: Option::default(),
+            timeout: Option::default(),
             view: String::default(),
         }
     }
@@ -261,7 +325,7 @@ impl ChangesQueryParamsStream {
     where
         T: Borrow<Feed>,
     {
-        self.feed = feed.borrow().to_string();
+        self.feed = feed.borrow().clone();
         self
     }
 
@@ -270,7 +334,7 @@ impl ChangesQueryParamsStream {
     where
         T: Borrow<Filter>,
     {
-        self.filter = filter.borrow().to_string();
+        self.filter = Some(filter.borrow().clone());
         self
     }
 
@@ -280,7 +344,7 @@ impl ChangesQueryParamsStream {
     ///
     /// Default is `60000`
     pub fn heartbeat(mut self, value: i64) -> Self {
-        self.heartbeat = value;
+        self.heartbeat = Some(value);
         self
     }
 
@@ -292,7 +356,7 @@ impl ChangesQueryParamsStream {
 
     /// Limit number of result rows to the specified value (note that using 0 here has the same effect as 1).
     pub fn limit(mut self, value: i64) -> Self {
-        self.limit = value;
+        self.limit = Some(value);
         self
     }
 
@@ -301,7 +365,16 @@ impl ChangesQueryParamsStream {
     /// By setting `seq_interval=<batch size>` , where `<batch size>` is the number of results requested per batch, load can be reduced on the source CouchDB database;
     /// computing the seq value across many shards (esp. in highly-sharded databases) is expensive in a heavily loaded CouchDB cluster.
     pub fn seq_interval(mut self, value: i64) -> Self {
-        self.seq_interval = value;
+        self.seq_interval = Some(value);
+        self
+    }
+
+    /// Start the results from the change immediately after the given update sequence, `now` starts from the current one.
+    pub fn since<A>(mut self, seq: A) -> Self
+    where
+        A: Into<String>,
+    {
+        self.since = Some(seq.into());
         self
     }
 
@@ -312,7 +385,7 @@ impl ChangesQueryParamsStream {
     where
         T: Borrow<Style>,
     {
-        self.style = style.borrow().to_string();
+        self.style = Some(style.borrow().clone());
         self
     }
 
@@ -322,7 +395,7 @@ impl ChangesQueryParamsStream {
     ///
     ///  Note that `60000` value is also the default maximum timeout to prevent undetected dead connections.
     pub fn timeout(mut self, value: i64) -> Self {
-        self.timeout = value;
+        self.timeout = Some(value);
         self
     }
 
@@ -340,6 +413,27 @@ impl ChangesQueryParamsStream {
         self.descending = enable;
         self
     }
+
+    /// Maximum silence on the connection before it is considered dead and reopened from the last received change,
+    /// consecutive reconnections wait an increasing delay, from 500ms up to 30s.
+    ///
+    /// Should be longer than the `heartbeat`, e.g. twice as long. Default is disabled.
+    pub fn inactivity_timeout(mut self, timeout: Duration) -> Self {
+        self.inactivity_timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn get_inactivity_timeout(&self) -> Option<Duration> {
+        self.inactivity_timeout
+    }
+
+    pub(crate) fn get_feed(&self) -> &Feed {
+        &self.feed
+    }
+
+    pub(crate) fn get_heartbeat(&self) -> Option<i64> {
+        self.heartbeat
+    }
 }
 
 impl ChangesQueryParams {
@@ -374,7 +468,7 @@ impl ChangesQueryParams {
     where
         T: Borrow<Filter>,
     {
-        self.filter = filter.borrow().to_string();
+        self.filter = Some(filter.borrow().clone());
         self
     }
 
@@ -386,7 +480,7 @@ impl ChangesQueryParams {
 
     /// Limit number of result rows to the specified value (note that using 0 here has the same effect as 1).
     pub fn limit(mut self, value: i64) -> Self {
-        self.limit = value;
+        self.limit = Some(value);
         self
     }
 
@@ -395,7 +489,16 @@ impl ChangesQueryParams {
     /// By setting `seq_interval=<batch size>` , where `<batch size>` is the number of results requested per batch, load can be reduced on the source CouchDB database;
     /// computing the seq value across many shards (esp. in highly-sharded databases) is expensive in a heavily loaded CouchDB cluster.
     pub fn seq_interval(mut self, value: i64) -> Self {
-        self.seq_interval = value;
+        self.seq_interval = Some(value);
+        self
+    }
+
+    /// Start the results from the change immediately after the given update sequence, `now` starts from the current one.
+    pub fn since<A>(mut self, seq: A) -> Self
+    where
+        A: Into<String>,
+    {
+        self.since = Some(seq.into());
         self
     }
 
@@ -406,7 +509,7 @@ impl ChangesQueryParams {
     where
         T: Borrow<Style>,
     {
-        self.style = style.borrow().to_string();
+        self.style = Some(style.borrow().clone());
         self
     }
 
@@ -425,3 +528,67 @@ impl ChangesQueryParams {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DbEvent;
+    use serde_json::json;
+
+    #[test]
+    fn known_events() {
+        for (event, expected) in [
+            ("created", DbEvent::Created),
+            ("updated", DbEvent::Updated),
+            ("deleted", DbEvent::Deleted),
+            ("ddoc_updated", DbEvent::DdocUpdated),
+        ] {
+            assert_eq!(
+                serde_json::from_value::<DbEvent>(json!(event)).unwrap(),
+                expected
+            );
+            assert_eq!(serde_json::to_value(&expected).unwrap(), json!(event));
+        }
+    }
+
+    #[test]
+    fn unknown_event_is_kept() {
+        let event = serde_json::from_value::<DbEvent>(json!("compacted")).unwrap();
+        assert_eq!(event, DbEvent::Other("compacted".to_string()));
+        assert_eq!(serde_json::to_value(&event).unwrap(), json!("compacted"));
+        assert_eq!(event.to_string(), "compacted");
+    }
+
+    #[test]
+    fn parse_doc_id() {
+        assert_eq!(
+            DbEvent::parse_doc_id("created:orders"),
+            Some(("orders".to_string(), DbEvent::Created))
+        );
+        assert_eq!(
+            DbEvent::parse_doc_id("ddoc_updated:orders"),
+            Some(("orders".to_string(), DbEvent::DdocUpdated))
+        );
+        assert_eq!(
+            DbEvent::parse_doc_id("compacted:orders"),
+            Some((
+                "orders".to_string(),
+                DbEvent::Other("compacted".to_string())
+            ))
+        );
+    }
+
+    #[test]
+    fn db_name_keeps_its_colons() {
+        assert_eq!(
+            DbEvent::parse_doc_id("updated:tenant:orders"),
+            Some(("tenant:orders".to_string(), DbEvent::Updated))
+        );
+    }
+
+    #[test]
+    fn malformed_doc_ids() {
+        assert_eq!(DbEvent::parse_doc_id("orders"), None);
+        assert_eq!(DbEvent::parse_doc_id(":orders"), None);
+        assert_eq!(DbEvent::parse_doc_id("created:"), None);
+    }
+}
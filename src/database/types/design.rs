@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{string_or_number, Sizes};
+
+/// Design document holding views and validation functions
+///
+/// ## Example
+/// ```ignore
+/// let design_doc = DesignDocument::new("users")
+///                     .view("by_email", View::new("function (doc) { emit(doc.email, null); }"))
+///                     .view("count", View::new("function (doc) { emit(doc.type, 1); }").reduce(BuiltInReduce::Count));
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct DesignDocument {
+    /// Design document ID, always starts with `_design/`
+    #[serde(rename = "_id")]
+    id: String,
+    /// Design document revision
+    #[serde(rename = "_rev", skip_serializing_if = "Option::is_none")]
+    rev: Option<String>,
+    /// Defines Query Server key to process design document functions. Default is `javascript`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    /// View functions definition
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    views: BTreeMap<String, View>,
+    /// Validate document update function source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validate_doc_update: Option<String>,
+    /// View default options
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<Value>,
+    /// Other fields, e.g. `shows`, `lists`, `updates` or `filters`, kept as they are
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl DesignDocument {
+    /// Create a design document, the `_design/` prefix is added to the name if missing
+    pub fn new<A>(name: A) -> Self
+    where
+        A: Into<String>,
+    {
+        let name = name.into();
+        let id = if name.starts_with("_design/") {
+            name
+        } else {
+            format!("_design/{}", name)
+        };
+        Self {
+            id,
+            ..Self::default()
+        }
+    }
+
+    /// Design document ID, always starts with `_design/`
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Design document name, the ID without the `_design/` prefix
+    pub fn name(&self) -> &str {
+        self.id.trim_start_matches("_design/")
+    }
+
+    /// Names of the views defined in the design document
+    pub fn view_names(&self) -> impl Iterator<Item = &str> {
+        self.views.keys().map(|name| name.as_str())
+    }
+
+    /// Defines Query Server key to process design document functions. Default is `javascript`
+    pub fn language<A>(mut self, language: A) -> Self
+    where
+        A: Into<String>,
+    {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Add a view function definition
+    pub fn view<A>(mut self, name: A, view: View) -> Self
+    where
+        A: Into<String>,
+    {
+        self.views.insert(name.into(), view);
+        self
+    }
+
+    /// Validate document update function source
+    pub fn validate_doc_update<A>(mut self, function: A) -> Self
+    where
+        A: Into<String>,
+    {
+        self.validate_doc_update = Some(function.into());
+        self
+    }
+
+    /// View default options, e.g. `{"partitioned": true}`
+    pub fn options(mut self, options: Value) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Set another field of the design document, e.g. `shows`, `lists`, `updates` or `filters`
+    pub fn field<A>(mut self, name: A, value: Value) -> Self
+    where
+        A: Into<String>,
+    {
+        self.extra.insert(name.into(), value);
+        self
+    }
+}
+
+/// View function definition
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct View {
+    /// Map function source
+    map: String,
+    /// Reduce function source or name of a built-in reduce function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reduce: Option<String>,
+}
+
+impl View {
+    /// Create a view with the given map function source
+    pub fn new<A>(map: A) -> Self
+    where
+        A: Into<String>,
+    {
+        Self {
+            map: map.into(),
+            reduce: None,
+        }
+    }
+
+    /// View emitting the value of `field` of every document having it as key, nested fields are separated by `.`,
+    /// e.g. `meta.created_at`
+    pub fn by_field<A>(field: A) -> Self
+    where
+        A: AsRef<str>,
+    {
+        let path = Value::from(field.as_ref().split('.').collect::<Vec<_>>());
+        Self::new(format!(
+            "function (doc) {{ var value = doc; {}.forEach(function (key) {{ value = value == null ? undefined : value[key]; }}); if (value != null) {{ emit(value, null); }} }}",
+            path
+        ))
+    }
+
+    /// Reduce function source or a [BuiltInReduce]
+    pub fn reduce<A>(mut self, reduce: A) -> Self
+    where
+        A: Into<String>,
+    {
+        self.reduce = Some(reduce.into());
+        self
+    }
+}
+
+/// Reduce functions implemented natively by CouchDB, faster than their JavaScript equivalent
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum BuiltInReduce {
+    /// Sum of the emitted numeric values, or arrays of numbers summed element wise
+    #[serde(rename = "_sum")]
+    Sum,
+    /// Number of emitted rows
+    #[serde(rename = "_count")]
+    Count,
+    /// Sum, count, min, max and sum of squares of the emitted numeric values, see [StatsReduce](super::StatsReduce)
+    #[serde(rename = "_stats")]
+    Stats,
+    /// Approximate number of distinct emitted keys
+    #[serde(rename = "_approx_count_distinct")]
+    ApproxCountDistinct,
+}
+
+impl std::fmt::Display for BuiltInReduce {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BuiltInReduce::Sum => write!(f, "_sum"),
+            BuiltInReduce::Count => write!(f, "_count"),
+            BuiltInReduce::Stats => write!(f, "_stats"),
+            BuiltInReduce::ApproxCountDistinct => write!(f, "_approx_count_distinct"),
+        }
+    }
+}
+
+impl From<BuiltInReduce> for String {
+    fn from(reduce: BuiltInReduce) -> Self {
+        reduce.to_string()
+    }
+}
+
+/// Response of an update handler function
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateHandlerResponse {
+    /// Body returned by the update function
+    pub body: String,
+    /// ID of the updated document, from the `X-Couch-Id` header
+    pub id: Option<String>,
+    /// New revision of the updated document, from the `X-Couch-Update-NewRev` header
+    pub rev: Option<String>,
+}
+
+/// Response of a show or list function
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RenderResponse {
+    /// Raw body rendered by the function
+    pub body: Vec<u8>,
+    /// Content type of the rendered body, from the `Content-Type` header
+    pub content_type: Option<String>,
+}
+
+/// Design document information, returned by `_design/{ddoc}/_info`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DesignDocInfo {
+    /// Design document name, without the `_design/` prefix
+    pub name: String,
+    /// View index information
+    pub view_index: ViewIndexInfo,
+}
+
+/// Information about the index shared by the views of a design document
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ViewIndexInfo {
+    /// `true` if the view index is being compacted
+    #[serde(default)]
+    pub compact_running: bool,
+    /// Language of the view functions
+    #[serde(default)]
+    pub language: String,
+    /// Purge sequence processed by the index
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub purge_seq: String,
+    /// MD5 signature of the views of the design document
+    #[serde(default)]
+    pub signature: String,
+    /// View index size
+    #[serde(default)]
+    pub sizes: Sizes,
+    /// Update sequence of the database indexed so far
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub update_seq: String,
+    /// `true` if the view index is being built
+    #[serde(default)]
+    pub updater_running: bool,
+    /// Number of clients waiting on views from this design document
+    #[serde(default)]
+    pub waiting_clients: i64,
+    /// `true` if there are outstanding commits to the underlying database that need to be processed
+    #[serde(default)]
+    pub waiting_commit: bool,
+}
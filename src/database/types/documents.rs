@@ -1,6 +1,14 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+
+#[cfg(feature = "reflect")]
 use bevy_reflect::Reflect;
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+use super::QueryWarning;
+use crate::NanoError;
 
 // Database response after document creation/deletion or update
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -9,8 +17,80 @@ pub struct DocResponse {
     pub ok: bool,
     /// Document ID
     pub id: String,
-    /// Revision MVCC token
+    /// Revision MVCC token, empty when the write was accepted in batch mode
+    #[serde(default)]
     pub rev: String,
+    /// Fields returned by CouchDB which are not mapped by this struct
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Document body together with its CouchDB metadata
+///
+/// ## Example
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Note {
+///     text: String,
+/// }
+///
+/// let doc = Document::new(Note { text: "hello".to_string() })
+///                 .id("note_1")
+///                 .access(["alice", "ops"]);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Document<T> {
+    /// Document ID
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Revision MVCC token
+    #[serde(rename = "_rev", default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    /// Users and roles allowed to access the document, needs the `access-ready` feature
+    #[serde(rename = "_access", default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<Vec<String>>,
+    /// Document body
+    #[serde(flatten)]
+    pub data: T,
+}
+
+impl<T> Document<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            id: None,
+            rev: None,
+            access: None,
+            data,
+        }
+    }
+
+    /// Document ID, generated when the document is created if not set
+    pub fn id<S>(mut self, id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Revision of the document to update
+    pub fn rev<S>(mut self, rev: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.rev = Some(rev.into());
+        self
+    }
+
+    /// Users and roles allowed to access the document, the database must be created with `access=true`
+    pub fn access<I, S>(mut self, access: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.access = Some(access.into_iter().map(|entry| entry.into()).collect());
+        self
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,6 +104,50 @@ pub struct GetMultipleDocs {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub update_seq: Option<String>,
 }
+/// Documents fetched by ID from `_all_docs`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocsByIdsResponse<T> {
+    /// Number of documents in the database
+    pub total_rows: Option<i64>,
+    /// Offset where the document list started
+    pub offset: Option<i64>,
+    /// One row per requested ID, in the same order as the requested IDs
+    pub rows: Vec<DocsByIdsRow<T>>,
+}
+
+/// Row of a document fetched by ID
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum DocsByIdsRow<T> {
+    /// The document exists or has been deleted
+    Found {
+        /// Document ID
+        id: String,
+        /// Requested ID
+        key: String,
+        /// Document revision information
+        value: DocRowValue,
+        /// Document body, present if `include_docs` is `true`, `None` if the document has been deleted
+        doc: Option<T>,
+    },
+    /// The document does not exist
+    Error {
+        /// Requested ID
+        key: String,
+        /// Error type, e.g. `not_found`
+        error: String,
+    },
+}
+
+/// Revision information of an `_all_docs` row
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocRowValue {
+    /// Current document revision
+    pub rev: String,
+    /// `true` if the document is deleted
+    pub deleted: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FindResponse {
     /// Vector of documents matching the search. In each matching document, the fields specified in the fields part of the request body are listed, along with their values.
@@ -36,6 +160,19 @@ pub struct FindResponse {
     pub warning: Option<String>,
     /// Execution stats
     pub execution_stats: Option<ExecutionStats>,
+    /// Fields returned by CouchDB which are not mapped by this struct
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl FindResponse {
+    /// Warnings reported by CouchDB about the query execution
+    pub fn warnings(&self) -> Vec<QueryWarning> {
+        self.warning
+            .as_deref()
+            .map(QueryWarning::parse)
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -45,10 +182,14 @@ pub struct ExecutionStats {
     pub total_quorum_docs_examined: i64,
     pub results_returned: i64,
     pub execution_time_ms: f64,
+    /// Fields returned by CouchDB which are not mapped by this struct
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Get document request params
-#[derive(Reflect, Default, Debug, Clone)]
+#[derive(Serialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
 pub struct GetDocRequestParams {
     /// Includes attachments bodies in response
     attachments: bool,
@@ -72,6 +213,12 @@ pub struct GetDocRequestParams {
     revs_info: bool,
     /// Deleted documents
     deleted: bool,
+    /// JSON array of the revisions to retrieve, or `all` for every leaf revision
+    open_revs: String,
+    /// JSON array of revisions, only the attachments added since them are included
+    atts_since: String,
+    /// Read quorum needed for the result
+    r: Option<i64>,
 }
 
 impl GetDocRequestParams {
@@ -147,10 +294,105 @@ impl GetDocRequestParams {
         self.deleted = enable;
         self
     }
+
+    /// Retrieve the given revisions of the document, even the deleted ones, instead of the winning one.
+    ///
+    /// The response is an array of [OpenRev], the revisions unknown to CouchDB are returned as [OpenRev::Missing].
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let params = GetDocRequestParams::default().open_revs(vec!["1-917fa23", "2-5bc4a8e"]);
+    /// let revs: Vec<OpenRev<Value>> = my_db.get_doc("my_doc", Some(&params)).await?;
+    /// ```
+    ///
+    /// More [info](https://docs.couchdb.org/en/stable/api/document/common.html#get--db-docid)
+    pub fn open_revs<A>(mut self, revs: Vec<A>) -> Self
+    where
+        A: Into<String>,
+    {
+        let revs: Vec<String> = revs.into_iter().map(|rev| rev.into()).collect();
+        self.open_revs = Value::from(revs).to_string();
+        self
+    }
+
+    /// Retrieve every leaf revision of the document, the response is an array of [OpenRev]
+    pub fn open_revs_all(mut self) -> Self {
+        self.open_revs = "all".to_string();
+        self
+    }
+
+    /// Include only the attachments added since the given revisions, the other attachments are returned as stubs.
+    /// Useful to fetch a document already known at those revisions without transferring its attachments again.
+    pub fn atts_since<A>(mut self, revs: Vec<A>) -> Self
+    where
+        A: Into<String>,
+    {
+        let revs: Vec<String> = revs.into_iter().map(|rev| rev.into()).collect();
+        self.atts_since = Value::from(revs).to_string();
+        self
+    }
+
+    /// Read quorum needed for the result, the number of replicas the document is read from
+    pub fn r(mut self, quorum: i64) -> Self {
+        self.r = Some(quorum);
+        self
+    }
+}
+
+/// Revision of a document requested with [open_revs](GetDocRequestParams::open_revs)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenRev<T = Value> {
+    /// The document at the requested revision
+    Ok(T),
+    /// The revision is unknown to CouchDB
+    Missing(String),
+}
+
+/// Delete document request options
+#[derive(Debug, Clone, Default)]
+pub struct DeleteDocParams {
+    /// Send the `X-Couch-Full-Commit: true` header, asking CouchDB to flush the deletion to disk before answering.
+    /// Ignored by CouchDB 3 and later which always commit.
+    full_commit: bool,
+    /// Send `batch=ok`, CouchDB answers with `202 Accepted` before the deletion is written to disk.
+    /// Faster but the deletion can be lost, and no revision is returned.
+    batch: bool,
+}
+
+impl DeleteDocParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send the `X-Couch-Full-Commit: true` header, asking CouchDB to flush the deletion to disk before answering.
+    /// Ignored by CouchDB 3 and later which always commit.
+    pub fn full_commit(mut self, enable: bool) -> Self {
+        self.full_commit = enable;
+        self
+    }
+
+    /// Send `batch=ok`, CouchDB answers with `202 Accepted` before the deletion is written to disk.
+    /// Faster but the deletion can be lost, and no revision is returned.
+    pub fn batch(mut self, enable: bool) -> Self {
+        self.batch = enable;
+        self
+    }
+
+    pub(crate) fn get_full_commit(&self) -> bool {
+        self.full_commit
+    }
+
+    pub(crate) fn get_batch(&self) -> bool {
+        self.batch
+    }
 }
 
 /// Get documents request params
+///
+/// Fields missing from a deserialized value take their default.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct GetDocsRequestParams {
     /// Include the Base64-encoded content of attachments in the documents that are included if `include_docs` is `true`.
     ///
@@ -165,10 +407,10 @@ pub struct GetDocsRequestParams {
     descending: Option<bool>,
     /// Stop returning records when the specified key is reached
     #[serde(skip_serializing_if = "Option::is_none")]
-    endkey: Option<String>,
+    endkey: Option<Value>,
     /// Alias for `endkey` param
     #[serde(skip_serializing_if = "Option::is_none")]
-    end_key: Option<String>,
+    end_key: Option<Value>,
     /// Stop returning records when the specified design document ID is reached.
     #[serde(skip_serializing_if = "Option::is_none")]
     endkey_docid: Option<String>,
@@ -189,10 +431,10 @@ pub struct GetDocsRequestParams {
     inclusive_end: Option<bool>,
     /// Return only design documents that match the specified key.
     #[serde(skip_serializing_if = "Option::is_none")]
-    key: Option<KeyValue>,
+    key: Option<Value>,
     /// Return only design documents that match the specified keys
     #[serde(skip_serializing_if = "Option::is_none")]
-    keys: Option<Vec<KeyValue>>,
+    keys: Option<Vec<Value>>,
     /// Include encoding information in attachment stubs if `include_docs` is `true` and the particular attachment is compressed.
     ///
     /// Ignored if `include_docs` isn’t `true`.
@@ -211,6 +453,18 @@ pub struct GetDocsRequestParams {
     /// Default is `0`
     #[serde(skip_serializing_if = "Option::is_none")]
     skip: Option<i64>,
+    /// Return records starting with the specified key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    startkey: Option<Value>,
+    /// Alias for `startkey` param
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_key: Option<Value>,
+    /// Return records starting with the specified document ID. Ignored if `startkey` is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    startkey_docid: Option<String>,
+    /// Alias for `startkey_docid` param
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_key_doc_id: Option<String>,
     ///  Sort returned rows. Setting this to false offers a performance boost.
     ///
     /// The total_rows and offset fields are not available when this is set to false. Default is `true`.
@@ -222,11 +476,55 @@ pub struct GetDocsRequestParams {
     ///  Whether to include in the response an `update_seq` value indicating the sequence id of the database the view reflects
     #[serde(skip_serializing_if = "Option::is_none")]
     update_seq: Option<bool>,
+    /// Whether or not the view in question should be updated prior to responding to the user. Default is `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update: Option<String>,
+    /// Legacy alternative to the `update` and `stable` params, allow the results from a stale view to be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale: Option<String>,
+    /// The start and end keys were set by `range`, so they are swapped whenever `descending` changes
+    #[serde(skip)]
+    ranged: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct KeyValue {
-    key: String,
+/// Whether or not a view should be updated prior to responding to the user
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub enum ViewUpdate {
+    /// Update the view before responding. Default.
+    #[default]
+    True,
+    /// Respond with the current state of the view, without updating it
+    False,
+    /// Respond with the current state of the view and update it after the response is sent
+    Lazy,
+}
+
+impl std::fmt::Display for ViewUpdate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ViewUpdate::True => write!(f, "true"),
+            ViewUpdate::False => write!(f, "false"),
+            ViewUpdate::Lazy => write!(f, "lazy"),
+        }
+    }
+}
+
+/// Legacy way of allowing the results from a stale view or index to be used
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum Stale {
+    /// Same as `update=false` and `stable=true`
+    Ok,
+    /// Same as `update=lazy` and `stable=true`, only supported by views
+    UpdateAfter,
+}
+
+impl std::fmt::Display for Stale {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Stale::Ok => write!(f, "ok"),
+            Stale::UpdateAfter => write!(f, "update_after"),
+        }
+    }
 }
 
 impl Default for GetDocsRequestParams {
@@ -251,7 +549,14 @@ impl Default for GetDocsRequestParams {
             skip: Some(0),
             sorted: Some(true),
             stable: Option::default(),
+            startkey: Option::default(),
+            start_key: Option::default(),
+            startkey_docid: Option::default(),
+            start_key_doc_id: Option::default(),
             update_seq: Option::default(),
+            update: Option::default(),
+            stale: Option::default(),
+            ranged: bool::default(),
         }
     }
 }
@@ -285,13 +590,21 @@ impl GetDocsRequestParams {
         self
     }
     /// Return only design documents that match the specified key.
-    pub fn key(mut self, key: KeyValue) -> Self {
-        self.key = Some(key);
+    ///
+    /// Keys can be any JSON value, e.g. a string, a number or an array for complex keys.
+    pub fn key<A>(mut self, key: A) -> Self
+    where
+        A: Into<Value>,
+    {
+        self.key = Some(key.into());
         self
     }
     /// Return only design documents that match the specified keys.
-    pub fn keys(mut self, keys: Vec<KeyValue>) -> Self {
-        self.keys = Some(keys);
+    pub fn keys<A>(mut self, keys: Vec<A>) -> Self
+    where
+        A: Into<Value>,
+    {
+        self.keys = Some(keys.into_iter().map(|key| key.into()).collect());
         self
     }
     /// Includes conflicts information in response. Ignored if isn’t `true`
@@ -301,15 +614,36 @@ impl GetDocsRequestParams {
     }
     ///  Return the design documents in descending by key order
     pub fn descending(mut self, enable: bool) -> Self {
+        // a range keeps its bounds whatever the order `range` and `descending` are called in
+        if self.ranged && self.is_descending() != enable {
+            std::mem::swap(&mut self.start_key, &mut self.end_key);
+        }
         self.descending = Some(enable);
         self
     }
+
+    /// Set the keys of a range given from its lowest to its highest key, in the order of `descending`
+    pub(crate) fn range_keys(mut self, lowest: Value, highest: Value) -> Self {
+        let (start_key, end_key) = match self.is_descending() {
+            true => (highest, lowest),
+            false => (lowest, highest),
+        };
+        self.start_key = Some(start_key);
+        self.end_key = Some(end_key);
+        self.ranged = true;
+        self
+    }
+
+    pub(crate) fn is_descending(&self) -> bool {
+        self.descending == Some(true)
+    }
     /// Stop returning records when the specified key is reached
     pub fn end_key<A>(mut self, key: A) -> Self
     where
-        A: Into<String>,
+        A: Into<Value>,
     {
         self.end_key = Some(key.into());
+        self.ranged = false;
         self
     }
     /// Stop returning records when the specified design document ID is reached.
@@ -344,6 +678,23 @@ impl GetDocsRequestParams {
         self.skip = Some(max_docs_skip);
         self
     }
+    /// Return records starting with the specified key
+    pub fn start_key<A>(mut self, key: A) -> Self
+    where
+        A: Into<Value>,
+    {
+        self.start_key = Some(key.into());
+        self.ranged = false;
+        self
+    }
+    /// Return records starting with the specified document ID. Ignored if `start_key` is not set.
+    pub fn start_key_doc_id<A>(mut self, doc_id: A) -> Self
+    where
+        A: Into<String>,
+    {
+        self.start_key_doc_id = Some(doc_id.into());
+        self
+    }
     /// Use the reduction function. Default is true when a reduce function is defined.
     pub fn reduce(mut self, enable: bool) -> Self {
         self.reduce = Some(enable);
@@ -359,6 +710,115 @@ impl GetDocsRequestParams {
         self.update_seq = Some(enable);
         self
     }
+    /// Whether or not the view in question should be updated prior to responding to the user. Default is `true`.
+    pub fn update<T>(mut self, update: T) -> Self
+    where
+        T: Borrow<ViewUpdate>,
+    {
+        self.update = Some(update.borrow().to_string());
+        self
+    }
+    /// Legacy alternative to the `update` and `stable` params, allow the results from a stale view to be used.
+    pub fn stale<T>(mut self, stale: T) -> Self
+    where
+        T: Borrow<Stale>,
+    {
+        self.stale = Some(stale.borrow().to_string());
+        self
+    }
+
+    /// Value set with [attachments](GetDocsRequestParams::attachments)
+    pub fn get_attachments(&self) -> Option<bool> {
+        self.attachments
+    }
+    /// Value set with [conflicts](GetDocsRequestParams::conflicts)
+    pub fn get_conflicts(&self) -> Option<bool> {
+        self.conflicts
+    }
+    /// Value set with [descending](GetDocsRequestParams::descending)
+    pub fn get_descending(&self) -> Option<bool> {
+        self.descending
+    }
+    /// Value set with [end_key](GetDocsRequestParams::end_key), or with the legacy `endkey` alias
+    pub fn get_end_key(&self) -> Option<&Value> {
+        self.end_key.as_ref().or(self.endkey.as_ref())
+    }
+    /// Value set with [end_key_doc_id](GetDocsRequestParams::end_key_doc_id), or with the legacy `endkey_docid` alias
+    pub fn get_end_key_doc_id(&self) -> Option<&str> {
+        self.end_key_doc_id
+            .as_deref()
+            .or(self.endkey_docid.as_deref())
+    }
+    /// Value set with [group](GetDocsRequestParams::group)
+    pub fn get_group(&self) -> Option<bool> {
+        self.group
+    }
+    /// Value set with [group_level](GetDocsRequestParams::group_level)
+    pub fn get_group_level(&self) -> Option<i64> {
+        self.group_level
+    }
+    /// Value set with [include_docs](GetDocsRequestParams::include_docs)
+    pub fn get_include_docs(&self) -> Option<bool> {
+        self.include_docs
+    }
+    /// Value set with [inclusive_end](GetDocsRequestParams::inclusive_end)
+    pub fn get_inclusive_end(&self) -> Option<bool> {
+        self.inclusive_end
+    }
+    /// Value set with [key](GetDocsRequestParams::key)
+    pub fn get_key(&self) -> Option<&Value> {
+        self.key.as_ref()
+    }
+    /// Value set with [keys](GetDocsRequestParams::keys)
+    pub fn get_keys(&self) -> Option<&[Value]> {
+        self.keys.as_deref()
+    }
+    /// Value set with [att_encoding_info](GetDocsRequestParams::att_encoding_info)
+    pub fn get_att_encoding_info(&self) -> Option<bool> {
+        self.att_encoding_info
+    }
+    /// Value set with [limit](GetDocsRequestParams::limit)
+    pub fn get_limit(&self) -> Option<i64> {
+        self.limit
+    }
+    /// Value set with [reduce](GetDocsRequestParams::reduce)
+    pub fn get_reduce(&self) -> Option<bool> {
+        self.reduce
+    }
+    /// Value set with [skip](GetDocsRequestParams::skip)
+    pub fn get_skip(&self) -> Option<i64> {
+        self.skip
+    }
+    /// Value set with [start_key](GetDocsRequestParams::start_key), or with the legacy `startkey` alias
+    pub fn get_start_key(&self) -> Option<&Value> {
+        self.start_key.as_ref().or(self.startkey.as_ref())
+    }
+    /// Value set with [start_key_doc_id](GetDocsRequestParams::start_key_doc_id), or with the legacy `startkey_docid` alias
+    pub fn get_start_key_doc_id(&self) -> Option<&str> {
+        self.start_key_doc_id
+            .as_deref()
+            .or(self.startkey_docid.as_deref())
+    }
+    /// Whether the returned rows are sorted
+    pub fn get_sorted(&self) -> Option<bool> {
+        self.sorted
+    }
+    /// Value set with [stable](GetDocsRequestParams::stable)
+    pub fn get_stable(&self) -> Option<bool> {
+        self.stable
+    }
+    /// Value set with [update_seq](GetDocsRequestParams::update_seq)
+    pub fn get_update_seq(&self) -> Option<bool> {
+        self.update_seq
+    }
+    /// Value set with [update](GetDocsRequestParams::update)
+    pub fn get_update(&self) -> Option<&str> {
+        self.update.as_deref()
+    }
+    /// Value set with [stale](GetDocsRequestParams::stale)
+    pub fn get_stale(&self) -> Option<&str> {
+        self.stale.as_deref()
+    }
 }
 
 /// Save Documents in bulk
@@ -419,25 +879,128 @@ pub struct BulkDocsRes {
     pub reason: Option<String>,
 }
 
+impl BulkDocsRes {
+    /// Why the document was not written, `None` if it was
+    pub fn failure(&self) -> Option<BulkDocError> {
+        let error = self.error.as_deref()?;
+        let reason = self.reason.clone().unwrap_or_default();
+        Some(match error {
+            "conflict" => BulkDocError::Conflict(reason),
+            "forbidden" => BulkDocError::Forbidden(reason),
+            "unauthorized" => BulkDocError::Unauthorized(reason),
+            error => BulkDocError::Other {
+                error: error.to_string(),
+                reason,
+            },
+        })
+    }
+}
+
+/// Reason a document of a `_bulk_docs` request was not written
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkDocError {
+    /// The given revision is not the current one
+    Conflict(String),
+    /// The document was rejected by a `validate_doc_update` function throwing `{forbidden: reason}`
+    Forbidden(String),
+    /// The document was rejected by a `validate_doc_update` function throwing `{unauthorized: reason}`
+    Unauthorized(String),
+    /// Any other error
+    Other { error: String, reason: String },
+}
+
+impl BulkDocError {
+    /// Status code CouchDB returns when the same error happens writing a single document
+    pub fn status_code(&self) -> u16 {
+        match self {
+            BulkDocError::Conflict(_) => 409,
+            BulkDocError::Forbidden(_) => 403,
+            BulkDocError::Unauthorized(_) => 401,
+            BulkDocError::Other { .. } => 500,
+        }
+    }
+}
+
+impl std::fmt::Display for BulkDocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BulkDocError::Conflict(reason) => write!(f, "conflict: {reason}"),
+            BulkDocError::Forbidden(reason) => write!(f, "forbidden: {reason}"),
+            BulkDocError::Unauthorized(reason) => write!(f, "unauthorized: {reason}"),
+            BulkDocError::Other { error, reason } => write!(f, "{error}: {reason}"),
+        }
+    }
+}
+
 /// Response of bulk saved documents
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BulkDocsResponse(pub Vec<BulkDocsRes>);
 
+impl BulkDocsResponse {
+    /// Documents which were not written, with the reason
+    pub fn failures(&self) -> impl Iterator<Item = (&str, BulkDocError)> {
+        self.0
+            .iter()
+            .filter_map(|res| res.failure().map(|error| (res.id.as_str(), error)))
+    }
+
+    /// Documents which were not written, with a [NanoError::DocRejected] carrying the reason
+    pub(crate) fn rejections(&self) -> Vec<(String, NanoError)> {
+        self.failures()
+            .map(|(id, error)| {
+                let err = NanoError::DocRejected {
+                    id: id.to_string(),
+                    error,
+                };
+                (id.to_string(), err)
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct BulkGetResponse {
-    pub results: Vec<BulkGetObj>,
+pub struct BulkGetResponse<T = Value> {
+    pub results: Vec<BulkGetObj<T>>,
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct BulkGetObj {
+pub struct BulkGetObj<T = Value> {
     pub id: String,
-    pub docs: Vec<BulkResult>,
+    pub docs: Vec<BulkResult<T>>,
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct BulkResult {
-    pub ok: Option<Value>,
+pub struct BulkResult<T = Value> {
+    pub ok: Option<T>,
     pub error: Option<ErrorBulkResponse>,
 }
 
+/// Bulk get request params
+#[derive(Serialize, Default, Debug, Clone)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct BulkGetRequestParams {
+    /// Give the revisions history of every document
+    revs: bool,
+    /// Include the Base64-encoded content of attachments in the documents
+    attachments: bool,
+}
+
+impl BulkGetRequestParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give the revisions history of every document
+    pub fn revs(mut self, enable: bool) -> Self {
+        self.revs = enable;
+        self
+    }
+
+    /// Include the Base64-encoded content of attachments in the documents
+    pub fn attachments(mut self, enable: bool) -> Self {
+        self.attachments = enable;
+        self
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ErrorBulkResponse {
     pub id: String,
@@ -515,3 +1078,115 @@ impl BulkDocQuery {
         self
     }
 }
+
+/// Response of purged documents
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PurgeResponse {
+    /// Purge sequence string
+    pub purge_seq: Option<String>,
+    /// Mapping of document ID to list of purged revisions
+    pub purged: HashMap<String, Vec<String>>,
+}
+
+/// Conflicted document
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocConflicts {
+    /// Document ID
+    pub id: String,
+    /// Revision chosen by CouchDB as winner, returned when reading the document
+    pub winning_rev: String,
+    /// Conflicting leaf revisions which lost against the winning one
+    pub conflicting_revs: Vec<String>,
+}
+
+/// Conflicting revisions of a document which have been deleted, left behind by resolved conflicts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeletedConflicts {
+    /// Document ID
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// Current revision
+    #[serde(rename = "_rev")]
+    pub rev: String,
+    /// Deleted leaf revisions, empty if there are none
+    #[serde(rename = "_deleted_conflicts", default)]
+    pub deleted_conflicts: Vec<String>,
+}
+
+/// Availability of a document revision
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RevisionStatus {
+    /// The revision body is stored in the database
+    Available,
+    /// The revision body has been removed by compaction, only its ID is known
+    Missing,
+    /// The revision is a deletion tombstone
+    Deleted,
+}
+
+/// Revision of a document with its availability
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevisionInfo {
+    /// Revision MVCC token
+    pub rev: String,
+    /// Availability of the revision
+    pub status: RevisionStatus,
+}
+
+impl RevisionInfo {
+    /// Revision generation, the number before the `-` in the revision token
+    pub fn generation(&self) -> u64 {
+        self.rev
+            .split_once('-')
+            .and_then(|(generation, _)| generation.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Revision hash, the part after the `-` in the revision token
+    pub fn hash(&self) -> &str {
+        self.rev
+            .split_once('-')
+            .map(|(_, hash)| hash)
+            .unwrap_or_default()
+    }
+}
+
+/// Revision history of a document, from the current revision to the oldest one
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevisionTree {
+    /// Document ID
+    pub id: String,
+    /// Known revisions, newest first
+    pub revisions: Vec<RevisionInfo>,
+}
+
+impl RevisionTree {
+    /// Current revision of the document
+    pub fn current(&self) -> Option<&RevisionInfo> {
+        self.revisions.first()
+    }
+
+    /// Revisions whose body can still be retrieved
+    pub fn available(&self) -> impl Iterator<Item = &RevisionInfo> {
+        self.revisions
+            .iter()
+            .filter(|info| info.status == RevisionStatus::Available)
+    }
+
+    /// Known revisions, oldest first
+    pub fn oldest_first(&self) -> impl Iterator<Item = &RevisionInfo> {
+        self.revisions.iter().rev()
+    }
+}
+
+/// Raw JSON body of a response together with its metadata, returned by the `*_raw_with_meta` methods
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// Response status code
+    pub status: u16,
+    /// `ETag` header without the double quotes, the revision of a document or the signature of a view
+    pub etag: Option<String>,
+    /// Raw JSON body
+    pub body: Bytes,
+}
@@ -1,9 +1,13 @@
 use std::borrow::Borrow;
 
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde::de::{DeserializeOwned, Deserializer, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::NanoError;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct Index {
     /// JSON object describing the index to create.
     index: IndexData,
@@ -27,18 +31,13 @@ pub struct Index {
     partitioned: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub enum IndexType {
     Text,
+    #[default]
     Json,
 }
 
-impl Default for IndexType {
-    fn default() -> Self {
-        Self::Json
-    }
-}
-
 impl std::fmt::Display for IndexType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -79,6 +78,16 @@ impl IndexData {
             .collect::<Vec<String>>();
         self
     }
+
+    /// Value set with [partial_filter_selector](IndexData::partial_filter_selector)
+    pub fn get_partial_filter_selector(&self) -> Option<&Value> {
+        self.partial_filter_selector.as_ref()
+    }
+
+    /// Value set with [fields](IndexData::fields)
+    pub fn get_fields(&self) -> &[String] {
+        &self.fields
+    }
 }
 
 impl Default for Index {
@@ -141,6 +150,86 @@ impl Index {
         self.partitioned = Some(enable);
         self
     }
+
+    /// Create an index on fields of the documents deserialized into `T`, the field names are the ones used by serde,
+    /// after any `rename` or `rename_all` attribute.
+    ///
+    /// Every field name, or the first segment of nested fields like `address.city`, is checked against the fields of `T`
+    /// so the index can't drift from the type being queried. [NanoError::UnknownField] is returned for a missing field.
+    ///
+    /// serde doesn't expose the fields of a struct having a `#[serde(flatten)]` field, for such types every field is
+    /// accepted, with the `tracing` feature a warning reports that the fields could not be checked.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// #[derive(Deserialize)]
+    /// #[serde(rename_all = "camelCase")]
+    /// struct User {
+    ///     first_name: String,
+    ///     age: u32,
+    /// }
+    ///
+    /// let index = Index::for_fields_of::<User>(&["firstName", "age"])?.name("by_name_and_age");
+    /// ```
+    pub fn for_fields_of<T>(fields: &[&str]) -> Result<Self, NanoError>
+    where
+        T: DeserializeOwned,
+    {
+        let Some(known_fields) = serde_field_names::<T>() else {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                type_name = std::any::type_name::<T>(),
+                "the fields of a type deserialized from a map can't be checked, index fields accepted as is"
+            );
+            return Ok(Self::default().add_index(IndexData::default().fields(fields.to_vec())));
+        };
+        for field in fields {
+            let root = field.split('.').next().unwrap_or_default();
+            if !known_fields.contains(&root) {
+                return Err(NanoError::UnknownField {
+                    field: field.to_string(),
+                    type_name: std::any::type_name::<T>().to_string(),
+                });
+            }
+        }
+        Ok(Self::default().add_index(IndexData::default().fields(fields.to_vec())))
+    }
+
+    /// ID of the design document in which the index will be created, always starts with `_design/`
+    pub fn design_doc_id(&self) -> Option<String> {
+        self.ddoc.as_ref().map(|ddoc| {
+            if ddoc.starts_with("_design/") {
+                ddoc.clone()
+            } else {
+                format!("_design/{}", ddoc)
+            }
+        })
+    }
+
+    /// Name of the index, if set
+    pub fn index_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Value set with [add_index](Index::add_index)
+    pub fn get_index(&self) -> &IndexData {
+        &self.index
+    }
+
+    /// Value set with [design_doc_index](Index::design_doc_index)
+    pub fn get_ddoc(&self) -> Option<&str> {
+        self.ddoc.as_deref()
+    }
+
+    /// Value set with [index_type](Index::index_type)
+    pub fn get_index_type(&self) -> &str {
+        &self.index_type
+    }
+
+    /// Value set with [partitioned](Index::partitioned)
+    pub fn get_partitioned(&self) -> Option<bool> {
+        self.partitioned
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -151,6 +240,9 @@ pub struct IndexResponse {
     pub id: String,
     /// Name of the index created
     pub name: String,
+    /// Fields returned by CouchDB which are not mapped by this struct
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -178,3 +270,124 @@ pub struct IndexFields {
     /// indexed fields
     fields: Vec<Value>,
 }
+
+/// Difference between the declared indexes and the ones present in a database
+#[derive(Debug, Clone, Default)]
+pub struct IndexDiff {
+    /// Declared indexes not present in the database
+    pub missing: Vec<Index>,
+    /// Declared indexes present in the database
+    pub present: Vec<IndexObj>,
+    /// Indexes present in the database which are not declared
+    pub unexpected: Vec<IndexObj>,
+}
+
+/// Field names of a struct as seen by serde, empty if `T` is not deserialized from a struct.
+/// `None` if `T` is deserialized from a map, as the structs having a `#[serde(flatten)]` field are, so its fields are unknown
+fn serde_field_names<T>() -> Option<&'static [&'static str]>
+where
+    T: DeserializeOwned,
+{
+    /// Deserializer recording the field names requested by `deserialize_struct`, or `None` for `deserialize_map`
+    struct FieldNamesRecorder<'a>(&'a mut Option<&'static [&'static str]>);
+
+    impl<'de, 'a> Deserializer<'de> for FieldNamesRecorder<'a> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            Err(serde::de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            *self.0 = Some(fields);
+            Err(serde::de::Error::custom("field names recorded"))
+        }
+
+        fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            *self.0 = None;
+            Err(serde::de::Error::custom("deserialized from a map"))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct enum identifier ignored_any
+        }
+    }
+
+    let mut fields: Option<&'static [&'static str]> = Some(&[]);
+    // the deserialization always fails, only the recorded field names matter
+    let _ = T::deserialize(FieldNamesRecorder(&mut fields));
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{serde_field_names, Index};
+    use crate::NanoError;
+    use serde::Deserialize;
+    use serde_json::{Map, Value};
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct User {
+        first_name: String,
+        age: u32,
+    }
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Flattened {
+        name: String,
+        #[serde(flatten)]
+        extra: Map<String, Value>,
+    }
+
+    #[test]
+    fn struct_fields_after_rename() {
+        assert_eq!(serde_field_names::<User>(), Some(&["firstName", "age"][..]));
+    }
+
+    #[test]
+    fn flattened_struct_fields_are_unknown() {
+        assert_eq!(serde_field_names::<Flattened>(), None);
+    }
+
+    #[test]
+    fn non_struct_has_no_fields() {
+        assert_eq!(serde_field_names::<String>(), Some(&[][..]));
+    }
+
+    #[test]
+    fn nested_fields_are_checked_by_their_root() {
+        assert!(Index::for_fields_of::<User>(&["firstName", "age.years"]).is_ok());
+    }
+
+    #[test]
+    fn unknown_field_is_rejected() {
+        assert!(matches!(
+            Index::for_fields_of::<User>(&["first_name"]),
+            Err(NanoError::UnknownField { field, .. }) if field == "first_name"
+        ));
+    }
+
+    #[test]
+    fn flattened_struct_accepts_every_field() {
+        assert!(Index::for_fields_of::<Flattened>(&["name", "anything"]).is_ok());
+    }
+}
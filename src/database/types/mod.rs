@@ -1,26 +1,88 @@
-use crate::{Convert, ParseQueryParams};
+use crate::{ClientConfig, ParseQueryParams};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
 
+mod attachments;
 mod changes;
+mod design;
 mod documents;
 mod index;
+mod partition;
 mod query;
+mod range;
+mod security;
+mod shards;
+mod view;
+pub use attachments::*;
 pub use changes::*;
+pub use design::*;
 pub use documents::*;
 pub use index::*;
+pub use partition::*;
 pub use query::*;
+pub use range::*;
+pub use security::*;
+pub use shards::*;
+pub use view::*;
 
-impl Convert for DBInfo {}
-impl Convert for ChangesResponse {}
-impl Convert for GetMultipleDocs {}
-impl Convert for DocResponse {}
-impl Convert for FindResponse {}
-impl Convert for DBOperationSuccess {}
+impl_display_json!(
+    DBInfo,
+    AttachmentDownload,
+    PartitionedDBInfo,
+    ChangesResponse,
+    GetMultipleDocs,
+    DocResponse,
+    FindResponse,
+    ExplainResponse,
+    DBOperationSuccess,
+    SecurityObject,
+    ShardsResponse,
+    DocShard,
+    PurgeResponse,
+    DocConflicts,
+    RevisionTree,
+    DesignDocument,
+    DesignDocInfo,
+    UpdateHandlerResponse,
+    RenderResponse,
+    BulkDocsRes,
+    BulkDocsResponse,
+    DeletedConflicts,
+    IndexResponse,
+    GetIndexResponse,
+);
+
+impl_from_json!(Index, IndexData, GetDocsRequestParams);
+
+impl<T: Serialize> std::fmt::Display for DocsByIdsResponse<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        crate::fmt_json(self, f)
+    }
+}
+
+impl<T: Serialize> std::fmt::Display for Document<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        crate::fmt_json(self, f)
+    }
+}
+
+impl<T: Serialize> std::fmt::Display for BulkGetResponse<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        crate::fmt_json(self, f)
+    }
+}
+
+impl<K: Serialize, V: Serialize> std::fmt::Display for ViewResponse<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        crate::fmt_json(self, f)
+    }
+}
 
 impl ParseQueryParams for ChangesQueryParamsStream {}
 impl ParseQueryParams for ChangesQueryParams {}
 impl ParseQueryParams for GetDocRequestParams {}
+impl ParseQueryParams for BulkGetRequestParams {}
 
 /// DB information
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -29,13 +91,17 @@ pub struct DBInfo {
     pub db_name: String,
     /// An opaque string that describes the purge state of the database.
     /// Do not rely on this string for counting the number of purge operations.
+    #[serde(default, deserialize_with = "string_or_number")]
     pub purge_seq: String,
     /// An opaque string that describes the state of the database.
     /// Do not rely on this string for counting the number of updates.
+    #[serde(deserialize_with = "string_or_number")]
     pub update_seq: String,
-    /// Database Size
+    /// Database Size, not reported by CouchDB 1.x
+    #[serde(default)]
     pub sizes: Sizes,
     /// Database properties
+    #[serde(default)]
     pub props: Props,
     /// Number of deleted documents
     pub doc_del_count: i64,
@@ -45,14 +111,19 @@ pub struct DBInfo {
     pub disk_format_version: i64,
     /// Set to `true` if the database compaction routine is operating on this database.
     pub compact_running: bool,
-    /// Cluster information
+    /// Cluster information, not reported by CouchDB 1.x
+    #[serde(default)]
     pub cluster: Cluster,
     /// Always "0". (Returned for legacy reasons.)
+    #[serde(deserialize_with = "string_or_number")]
     pub instance_start_time: String,
+    /// Fields returned by CouchDB which are not mapped by this struct
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Cluster information
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Cluster {
     /// Shards. The number of range partitions.
     pub q: i64,
@@ -65,17 +136,50 @@ pub struct Cluster {
 }
 
 /// Database Size
-#[derive(Debug, Serialize, Deserialize, Clone)]
+///
+/// Every size defaults to `0` when missing, partitions info for example does not report the `file` size.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 pub struct Sizes {
     /// The size of the database file on disk in bytes.
     /// Views indexes are not included in the calculation.
+    #[serde(default)]
     pub file: i64,
     /// The uncompressed size of database contents in bytes.
+    #[serde(default)]
     pub external: i64,
     /// he size of live data inside the database, in bytes.
+    #[serde(default)]
     pub active: i64,
 }
+
+impl DBInfo {
+    /// Whether the document counts, the sizes or the compaction state differ from `other`
+    pub fn changed_from(&self, other: &DBInfo) -> bool {
+        self.doc_count != other.doc_count
+            || self.doc_del_count != other.doc_del_count
+            || self.sizes != other.sizes
+            || self.compact_running != other.compact_running
+    }
+}
+
+/// Partition information of a partitioned database
 #[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PartitionedDBInfo {
+    /// Database name
+    pub db_name: String,
+    /// Partition name
+    pub partition: String,
+    /// Partition size
+    pub sizes: Sizes,
+    /// Number of documents in the partition
+    pub doc_count: i64,
+    /// Number of deleted documents in the partition
+    pub doc_del_count: i64,
+    /// Fields returned by CouchDB which are not mapped by this struct
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Props {
     /// If present and true, this indicates that the database is partitioned.
     pub partitioned: Option<bool>,
@@ -84,6 +188,8 @@ pub struct Props {
 /// Connected Database
 ///
 /// After creating a database, when connecting to a database from now on this struct will be used to interact with it
+///
+/// Cloning is cheap, the clones share the connection pool of the [Nano](crate::Nano) node they come from.
 #[derive(Debug, Clone)]
 pub struct DBInUse {
     /// CouchDB node url
@@ -92,6 +198,8 @@ pub struct DBInUse {
     pub db_name: String,
     /// reqwest client which will be used to perform HTTP requests to CouchDB server
     pub client: Client,
+    /// Settings inherited from the CouchDB node
+    pub(crate) config: ClientConfig,
 }
 
 /// Success creating/deleting a database response from CouchDB
@@ -100,3 +208,27 @@ pub struct DBOperationSuccess {
     /// Operation status
     pub ok: bool,
 }
+
+/// Deserialize sequences and other values which are strings on CouchDB 2.x and 3.x and numbers on older versions
+pub(crate) fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(value) => value,
+        Value::Null => String::default(),
+        value => value.to_string(),
+    })
+}
+
+/// Same as [string_or_number] for optional values
+pub(crate) fn option_string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(value) => Some(value),
+        Value::Null => None,
+        value => Some(value.to_string()),
+    })
+}
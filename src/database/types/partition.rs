@@ -0,0 +1,164 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::NanoError;
+
+/// Document ID of a partitioned database, formatted as `<partition>:<id>`
+///
+/// It can be used wherever a document ID is accepted, e.g. `get_doc` or `create_or_update_doc` with `Some(id.as_str())`.
+///
+/// ## Example
+/// ```ignore
+/// let id = PartitionedId::new("sensor-1", "reading-42")?;
+/// let doc: Value = my_db.get_doc(&id, None).await?;
+/// my_db.create_or_update_doc(&doc, Some(id.as_str()), None).await?;
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct PartitionedId {
+    /// Full document ID
+    id: String,
+    /// Length of the partition name
+    partition_len: usize,
+}
+
+impl PartitionedId {
+    /// Create a partitioned document ID, the partition must not be empty, contain `:` or start with `_`
+    /// and the document ID must not be empty
+    pub fn new<A, B>(partition: A, id: B) -> Result<Self, NanoError>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        let (partition, id) = (partition.as_ref(), id.as_ref());
+        if partition.is_empty() || partition.contains(':') || partition.starts_with('_') {
+            return Err(NanoError::InvalidPartitionedId(format!(
+                "invalid partition `{}`, it must not be empty, contain `:` or start with `_`",
+                partition
+            )));
+        }
+        if id.is_empty() {
+            return Err(NanoError::InvalidPartitionedId(
+                "the document ID must not be empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            id: format!("{}:{}", partition, id),
+            partition_len: partition.len(),
+        })
+    }
+
+    /// Partition name
+    pub fn partition(&self) -> &str {
+        &self.id[..self.partition_len]
+    }
+
+    /// Document ID inside the partition, without the partition name
+    pub fn doc_id(&self) -> &str {
+        &self.id[self.partition_len + 1..]
+    }
+
+    /// Full document ID, `<partition>:<id>`
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+}
+
+impl AsRef<str> for PartitionedId {
+    fn as_ref(&self) -> &str {
+        &self.id
+    }
+}
+
+impl std::fmt::Display for PartitionedId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+impl FromStr for PartitionedId {
+    type Err = NanoError;
+
+    /// Parse a full document ID, `<partition>:<id>`
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        match id.split_once(':') {
+            Some((partition, id)) => Self::new(partition, id),
+            None => Err(NanoError::InvalidPartitionedId(format!(
+                "`{}` is missing the partition, expected `<partition>:<id>`",
+                id
+            ))),
+        }
+    }
+}
+
+impl TryFrom<String> for PartitionedId {
+    type Error = NanoError;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        id.parse()
+    }
+}
+
+impl From<PartitionedId> for String {
+    fn from(id: PartitionedId) -> Self {
+        id.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::PartitionedId;
+    use crate::NanoError;
+
+    fn is_invalid(id: Result<PartitionedId, NanoError>) -> bool {
+        matches!(id, Err(NanoError::InvalidPartitionedId(_)))
+    }
+
+    #[test]
+    fn parse_full_id() {
+        let id: PartitionedId = "sensor-1:reading-42".parse().unwrap();
+        assert_eq!(id.partition(), "sensor-1");
+        assert_eq!(id.doc_id(), "reading-42");
+        assert_eq!(id.as_str(), "sensor-1:reading-42");
+        assert_eq!(id, PartitionedId::new("sensor-1", "reading-42").unwrap());
+    }
+
+    #[test]
+    fn doc_id_keeps_its_colons() {
+        let id: PartitionedId = "sensor-1:2024:03:01".parse().unwrap();
+        assert_eq!(id.partition(), "sensor-1");
+        assert_eq!(id.doc_id(), "2024:03:01");
+    }
+
+    #[test]
+    fn missing_partition_is_rejected() {
+        assert!(is_invalid("reading-42".parse()));
+        assert!(is_invalid(":reading-42".parse()));
+        assert!(is_invalid("sensor-1:".parse()));
+    }
+
+    #[test]
+    fn leading_underscore_is_rejected() {
+        assert!(is_invalid("_design:by_sensor".parse()));
+        assert!(is_invalid(PartitionedId::new("_sensor", "reading-42")));
+    }
+
+    #[test]
+    fn colon_in_partition_is_rejected() {
+        assert!(is_invalid(PartitionedId::new("sensor:1", "reading-42")));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let id: PartitionedId = serde_json::from_value(json!("sensor-1:reading-42")).unwrap();
+        assert_eq!(
+            serde_json::to_value(&id).unwrap(),
+            json!("sensor-1:reading-42")
+        );
+        assert!(serde_json::from_value::<PartitionedId>(json!("reading-42")).is_err());
+    }
+}
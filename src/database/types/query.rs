@@ -1,11 +1,16 @@
+use std::borrow::Borrow;
+
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Map, Value};
+
+use super::Stale;
+use crate::{NanoError, QueryViolation};
 
 /// Create a Query to CouchDB
 ///
 /// ## Example
 /// Lets assume we need to make this query
-/// ```
+/// ```json
 /// {
 ///     "selector": {
 ///       "year": {
@@ -21,7 +26,7 @@ use serde_json::Value;
 /// }
 /// ```
 /// This are the steps to transform in a MangoQuery type:
-/// ```
+/// ```ignore
 /// // we must use the `json!()` macro because in a struct we cant have keys which starts with `$`
 /// let selector = serde_json::json!({
 ///     "year": {
@@ -37,6 +42,14 @@ use serde_json::Value;
 ///             .fields(fields);
 ///
 /// ```
+/// A query stored as JSON, e.g. in a config file, can be loaded and inspected:
+/// ```ignore
+/// let mango_query: MangoQuery = std::fs::read_to_string("query.json")?.parse()?;
+/// // or from a `serde_json::Value`
+/// let mango_query = MangoQuery::try_from(value)?;
+///
+/// assert_eq!(mango_query.get_limit(), Some(10));
+/// ```
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct MangoQuery {
     /// Selectors are expressed as a JSON object describing documents of interest. Within this structure, you can apply conditional logic using specially named fields.
@@ -56,6 +69,9 @@ pub struct MangoQuery {
     /// Instruct a query to use a specific index.
     #[serde(skip_serializing_if = "Option::is_none")]
     use_index: Option<Vec<String>>,
+    /// Partition the query runs on, sent in the url of `_partition/{partition}/_find` instead of the body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partition: Option<String>,
     /// Include conflicted documents if `true`. Intended use is to easily find conflicted documents, without an index or view. Default is `false`
     #[serde(skip_serializing_if = "Option::is_none")]
     conflicts: Option<bool>,
@@ -78,15 +94,66 @@ pub struct MangoQuery {
     /// Whether or not the view results should be returned from a `stable` set of shards
     #[serde(skip_serializing_if = "Option::is_none")]
     stable: Option<bool>,
+    /// Legacy combination of `update=false` and `stable=true`, only `ok` is accepted by `_find`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale: Option<String>,
     /// Include execution statistics in the query response, Default `false`
     #[serde(skip_serializing_if = "Option::is_none")]
     execution_stats: Option<bool>,
 }
 
+/// Field holding the time a document was soft deleted with [DBInUse::soft_delete](super::DBInUse::soft_delete)
+pub const SOFT_DELETED_FIELD: &str = "deleted_at";
+
 impl MangoQuery {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Create a query from its JSON, e.g. supplied by a user, validating it before it is sent to `_find`
+    ///
+    /// Every problem found is reported in [NanoError::InvalidQuery] with the JSON Pointer of the invalid value:
+    /// unknown keys, a missing or non object selector, sort fields with different directions, values of the wrong type.
+    /// `use_index` can be given as a string or as an array, like `_find` accepts it.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let query = MangoQuery::from_json(json!({
+    ///     "selector": { "year": { "$gt": 2010 } },
+    ///     "sort": [{ "year": "asc" }, { "title": "desc" }],
+    ///     "limt": 10
+    /// }));
+    /// // Invalid Mango query: /limt: unknown key, /sort/1: direction `desc` differs from `asc` of the previous fields
+    /// assert!(matches!(query, Err(NanoError::InvalidQuery(_))));
+    /// ```
+    pub fn from_json(query: Value) -> Result<Self, NanoError> {
+        let mut query = match query {
+            Value::Object(query) => query,
+            _ => return Err(invalid_query("", "the query must be a JSON object")),
+        };
+        if let Some(Value::String(index)) = query.get("use_index") {
+            let index = Value::Array(vec![Value::String(index.clone())]);
+            query.insert("use_index".to_string(), index);
+        }
+        let violations = validate_mango_query(&query);
+        if !violations.is_empty() {
+            return Err(NanoError::InvalidQuery(violations));
+        }
+        serde_json::from_value(Value::Object(query))
+            .map_err(|err| invalid_query("", &err.to_string()))
+    }
+
+    /// Exclude the documents soft deleted with [DBInUse::soft_delete](super::DBInUse::soft_delete),
+    /// call it after setting the selector
+    pub fn exclude_soft_deleted(mut self) -> Self {
+        let not_deleted = json!({ SOFT_DELETED_FIELD: { "$exists": false } });
+        self.selector = match self.selector {
+            Value::Null => not_deleted,
+            Value::Object(fields) if fields.is_empty() => not_deleted,
+            selector => json!({ "$and": [selector, not_deleted] }),
+        };
+        self
+    }
     /// Selectors are expressed as a JSON object describing documents of interest. Within this structure, you can apply conditional logic using specially named fields.
     ///
     /// Whilst selectors have some similarities with MongoDB query documents, these arise from a similarity of purpose and do not necessarily extend to commonality of function or result.
@@ -94,7 +161,7 @@ impl MangoQuery {
     ///
     /// ## Example simle selector
     /// This selector matches all documents whose “director” field has the value “Lars von Trier”.
-    /// ```
+    /// ```json
     /// {
     ///     "selector": {
     ///         "title": "Live And Let Die"
@@ -112,7 +179,7 @@ impl MangoQuery {
     /// A more complex selector enables you to specify the values for field of nested objects, or subfields.
     /// For example, you might use a standard JSON structure for specifying a field and subfield.
     /// ## Example of a field and subfield selector, using a standard JSON structure:
-    /// ```
+    /// ```json
     /// {
     ///     "selector" {
     ///         "imdb": {
@@ -122,7 +189,7 @@ impl MangoQuery {
     /// }
     /// ```
     /// An abbreviated equivalent uses a dot notation to combine the field and subfield names into a single name.
-    /// ```
+    /// ```json
     /// {
     ///     "selector" {
     ///         "imdb.rating": 8
@@ -130,7 +197,7 @@ impl MangoQuery {
     /// }
     /// ```
     /// An example of the `$eq` operator used with database indexed on the field "year"
-    /// ```
+    /// ```json
     /// {
     ///     "selector": {
     ///       "year": {
@@ -146,7 +213,7 @@ impl MangoQuery {
     /// }
     /// ```
     /// Example of using explicit `$and` and `$eq` operators
-    /// ```
+    /// ```json
     /// {
     ///     "selector": {
     ///         "$and": [
@@ -179,11 +246,11 @@ impl MangoQuery {
     ///
     /// The direction value is `asc` for ascending, and `desc` for descending. If you omit the direction value, the default `asc` is used.
     /// ## Example sorting by 2 fields
-    /// ```
+    /// ```json
     /// [{"fieldName1": "desc"}, {"fieldName2": "desc" }]
     /// ```
     /// ## Example sorting by 2 fields,assuming `default` direction for both
-    /// ```
+    /// ```json
     /// ["fieldName1", "fieldName2"]
     /// ```
     /// A typical requirement is to search for some content using a selector, then to sort the results according to the specified field, in the required direction.
@@ -197,7 +264,7 @@ impl MangoQuery {
     ///
     /// Find does not support multiple fields with different sort orders, so the directions must be either all ascending or all descending.
     /// ## Example of a simple query using sorting:
-    /// ```
+    /// ```json
     /// {
     ///     "selector": {"Actor_name": "Robert De Niro"},
     ///     "sort": [{"Actor_name": "asc"}, {"Movie_runtime": "asc"}]
@@ -217,7 +284,7 @@ impl MangoQuery {
     ///
     /// Only the specified filter fields are included, in the response. There is no automatic inclusion of the `_id` or other metadata fields when a field list is included.
     /// ## Example of selective retrieval of fields from matching documents:
-    /// ```
+    /// ```json
     /// {
     ///     "selector": { "Actor_name": "Robert De Niro" },
     ///     "fields": ["Actor_name", "Movie_year", "_id", "_rev"]
@@ -245,17 +312,31 @@ impl MangoQuery {
         self.skip = Some(docs_to_skip);
         self
     }
-    /// Instruct a query to use a specific index.
-    pub fn use_index<A>(mut self, index_to_use: Vec<A>) -> Self
+    /// Instruct a query to use a specific index, given as a design document, a `(design document, index name)`
+    /// tuple or a vector of them.
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let query = MangoQuery::new()
+    ///     .selector(json!({ "year": { "$gt": 2010 } }))
+    ///     .use_index(("_design/movies", "by_year"));
+    /// ```
+    pub fn use_index<I>(mut self, index_to_use: I) -> Self
+    where
+        I: Into<UseIndex>,
+    {
+        self.use_index = Some(index_to_use.into().0);
+        self
+    }
+    /// Run the query on a single partition of a partitioned database, it is sent to `_partition/{partition}/_find`
+    /// instead of `_find`. The `partition` key is accepted in JSON queries too.
+    ///
+    /// More [info](https://docs.couchdb.org/en/stable/api/partitioned-dbs.html#db-partition-partition-id-find)
+    pub fn partition<A>(mut self, partition: A) -> Self
     where
         A: Into<String>,
     {
-        self.use_index = Some(
-            index_to_use
-                .into_iter()
-                .map(|a| a.into())
-                .collect::<Vec<String>>(),
-        );
+        self.partition = Some(partition.into());
         self
     }
     /// Include conflicted documents if `true`. Intended use is to easily find conflicted documents, without an index or view. Default is `false`
@@ -293,9 +374,525 @@ impl MangoQuery {
         self.stable = Some(enable);
         self
     }
+    /// Legacy combination of `update=false` and `stable=true`, only `ok` is accepted by `_find`,
+    /// [Stale::UpdateAfter] is sent as `update=false` and `stable=true` instead
+    pub fn stale<T>(mut self, stale: T) -> Self
+    where
+        T: Borrow<Stale>,
+    {
+        match stale.borrow() {
+            Stale::Ok => self.stale = Some(Stale::Ok.to_string()),
+            Stale::UpdateAfter => {
+                self.update = Some(false);
+                self.stable = Some(true);
+            }
+        }
+        self
+    }
     /// Include execution statistics in the query response, Default `false`
     pub fn execution_stats(mut self, enable: bool) -> Self {
         self.execution_stats = Some(enable);
         self
     }
+
+    /// Value set with [selector](MangoQuery::selector)
+    pub fn get_selector(&self) -> &Value {
+        &self.selector
+    }
+    /// Value set with [sort](MangoQuery::sort)
+    pub fn get_sort(&self) -> Option<&[Value]> {
+        self.sort.as_deref()
+    }
+    /// Value set with [fields](MangoQuery::fields)
+    pub fn get_fields(&self) -> Option<&[String]> {
+        self.fields.as_deref()
+    }
+    /// Value set with [limit](MangoQuery::limit)
+    pub fn get_limit(&self) -> Option<i64> {
+        self.limit
+    }
+    /// Value set with [skip](MangoQuery::skip)
+    pub fn get_skip(&self) -> Option<i64> {
+        self.skip
+    }
+    /// Value set with [use_index](MangoQuery::use_index)
+    pub fn get_use_index(&self) -> Option<&[String]> {
+        self.use_index.as_deref()
+    }
+    /// Value set with [partition](MangoQuery::partition)
+    pub fn get_partition(&self) -> Option<&str> {
+        self.partition.as_deref()
+    }
+    /// Value set with [conflicts](MangoQuery::conflicts)
+    pub fn get_conflicts(&self) -> Option<bool> {
+        self.conflicts
+    }
+    /// Value set with [r](MangoQuery::r)
+    pub fn get_r(&self) -> Option<i64> {
+        self.r
+    }
+    /// Value set with [bookmark](MangoQuery::bookmark)
+    pub fn get_bookmark(&self) -> Option<&str> {
+        self.bookmark.as_deref()
+    }
+    /// Value set with [update](MangoQuery::update)
+    pub fn get_update(&self) -> Option<bool> {
+        self.update
+    }
+    /// Value set with [stable](MangoQuery::stable)
+    pub fn get_stable(&self) -> Option<bool> {
+        self.stable
+    }
+    /// Value set with [stale](MangoQuery::stale)
+    pub fn get_stale(&self) -> Option<&str> {
+        self.stale.as_deref()
+    }
+    /// Value set with [execution_stats](MangoQuery::execution_stats)
+    pub fn get_execution_stats(&self) -> Option<bool> {
+        self.execution_stats
+    }
+}
+
+/// Index a [MangoQuery] is instructed to use, a design document optionally followed by the index name
+#[derive(Debug, Clone, PartialEq)]
+pub struct UseIndex(Vec<String>);
+
+impl From<&str> for UseIndex {
+    fn from(ddoc: &str) -> Self {
+        Self(vec![ddoc.to_string()])
+    }
+}
+
+impl From<String> for UseIndex {
+    fn from(ddoc: String) -> Self {
+        Self(vec![ddoc])
+    }
+}
+
+impl<A, B> From<(A, B)> for UseIndex
+where
+    A: Into<String>,
+    B: Into<String>,
+{
+    fn from((ddoc, name): (A, B)) -> Self {
+        Self(vec![ddoc.into(), name.into()])
+    }
+}
+
+impl<A> From<Vec<A>> for UseIndex
+where
+    A: Into<String>,
+{
+    fn from(index: Vec<A>) -> Self {
+        Self(index.into_iter().map(|a| a.into()).collect())
+    }
+}
+
+impl std::convert::TryFrom<Value> for MangoQuery {
+    type Error = NanoError;
+
+    /// Same as [MangoQuery::from_json]
+    fn try_from(query: Value) -> Result<Self, Self::Error> {
+        Self::from_json(query)
+    }
+}
+
+impl std::str::FromStr for MangoQuery {
+    type Err = NanoError;
+
+    /// Parse the JSON and validate it with [MangoQuery::from_json]
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        Self::from_json(serde_json::from_str(query)?)
+    }
+}
+
+fn invalid_query(path: &str, message: &str) -> NanoError {
+    NanoError::InvalidQuery(vec![violation(path, message)])
+}
+
+fn violation(path: &str, message: &str) -> QueryViolation {
+    QueryViolation {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Check the keys of a Mango query, returning every problem found
+fn validate_mango_query(query: &Map<String, Value>) -> Vec<QueryViolation> {
+    let mut violations = vec![];
+    match query.get("selector") {
+        None => violations.push(violation("/selector", "missing selector")),
+        Some(Value::Object(_)) => {}
+        Some(_) => violations.push(violation("/selector", "the selector must be a JSON object")),
+    }
+    for (key, value) in query {
+        let path = format!("/{}", key);
+        let expected = match key.as_str() {
+            "selector" => continue,
+            "sort" => {
+                violations.extend(validate_sort(value));
+                continue;
+            }
+            "fields" | "use_index" => match value.as_array() {
+                Some(values) => {
+                    for (i, value) in values.iter().enumerate() {
+                        if !value.is_string() {
+                            violations
+                                .push(violation(&format!("{}/{}", path, i), "expected a string"));
+                        }
+                    }
+                    if key == "use_index" && !(1..=2).contains(&values.len()) {
+                        violations.push(violation(
+                            &path,
+                            "expected a design document and optionally an index name",
+                        ));
+                    }
+                    continue;
+                }
+                None => "an array of strings",
+            },
+            "limit" | "skip" if value.as_u64().is_some() => continue,
+            "limit" | "skip" => "a non negative integer",
+            "r" if value.as_u64().is_some_and(|r| r >= 1) => continue,
+            "r" => "a positive integer",
+            "conflicts" | "update" | "stable" | "execution_stats" if value.is_boolean() => continue,
+            "conflicts" | "update" | "stable" | "execution_stats" => "a boolean",
+            "bookmark" | "partition" if value.is_string() => continue,
+            "bookmark" | "partition" => "a string",
+            "stale" if value == "ok" => continue,
+            "stale" => "`ok`",
+            _ => {
+                violations.push(violation(&path, "unknown key"));
+                continue;
+            }
+        };
+        violations.push(violation(&path, &format!("expected {}", expected)));
+    }
+    violations
+}
+
+/// Check that every sort field is a field name or a `{"field": "asc" | "desc"}` object,
+/// all sorted in the same direction
+fn validate_sort(sort: &Value) -> Vec<QueryViolation> {
+    let fields = match sort.as_array() {
+        Some(fields) => fields,
+        None => return vec![violation("/sort", "expected an array")],
+    };
+    let mut violations = vec![];
+    let mut first_direction = None;
+    for (i, field) in fields.iter().enumerate() {
+        let path = format!("/sort/{}", i);
+        let direction = match field {
+            Value::String(_) => "asc",
+            Value::Object(pair) if pair.len() == 1 => match pair.values().next() {
+                Some(Value::String(direction)) if direction == "asc" || direction == "desc" => {
+                    direction.as_str()
+                }
+                _ => {
+                    violations.push(violation(&path, "the direction must be `asc` or `desc`"));
+                    continue;
+                }
+            },
+            _ => {
+                violations.push(violation(
+                    &path,
+                    "expected a field name or an object with a single field and its direction",
+                ));
+                continue;
+            }
+        };
+        match first_direction {
+            None => first_direction = Some(direction),
+            Some(first) if first != direction => violations.push(violation(
+                &path,
+                &format!(
+                    "direction `{}` differs from `{}` of the previous fields",
+                    direction, first
+                ),
+            )),
+            Some(_) => {}
+        }
+    }
+    violations
+}
+
+/// Query plan warning reported by `_find` or deduced from `_explain`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum QueryWarning {
+    /// No index matches the query, every document of the database is scanned
+    NoMatchingIndex,
+    /// The index requested with `use_index` was not used, the query planner chose another one
+    IndexNotUsed(String),
+    /// The number of examined documents is high compared to the number of returned results
+    TooManyDocsExamined,
+    /// The query is answered scanning `_all_docs`
+    FullScan,
+    /// Warning unknown to this crate
+    Unknown(String),
+}
+
+impl QueryWarning {
+    /// Parse the `warning` field of a `_find` response, it may hold multiple warnings separated by new lines
+    pub fn parse(warning: &str) -> Vec<QueryWarning> {
+        warning
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if line.starts_with("No matching index found") {
+                    QueryWarning::NoMatchingIndex
+                } else if line.starts_with("The number of documents examined is high") {
+                    QueryWarning::TooManyDocsExamined
+                } else if line.contains("was not used because") {
+                    QueryWarning::IndexNotUsed(line.to_string())
+                } else {
+                    QueryWarning::Unknown(line.to_string())
+                }
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for QueryWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            QueryWarning::NoMatchingIndex => write!(f, "no matching index found"),
+            QueryWarning::IndexNotUsed(warning) => write!(f, "{}", warning),
+            QueryWarning::TooManyDocsExamined => {
+                write!(
+                    f,
+                    "too many documents examined compared to the returned ones"
+                )
+            }
+            QueryWarning::FullScan => write!(f, "the query scans every document"),
+            QueryWarning::Unknown(warning) => write!(f, "{}", warning),
+        }
+    }
+}
+
+/// Query plan returned by `_explain`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExplainResponse {
+    /// Database name
+    pub dbname: String,
+    /// Index used to answer the query
+    pub index: ExplainIndex,
+    /// Selector used by the query
+    pub selector: Value,
+    /// Query options
+    pub opts: Value,
+    /// Maximum number of results returned
+    pub limit: i64,
+    /// Number of skipped results
+    pub skip: i64,
+    /// Fields returned by the query
+    pub fields: Value,
+    /// Fields returned by CouchDB which are not mapped by this struct
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ExplainResponse {
+    /// Warnings deduced from the query plan
+    pub fn warnings(&self) -> Vec<QueryWarning> {
+        if self.index.index_type == "special" {
+            return vec![QueryWarning::FullScan];
+        }
+        vec![]
+    }
+}
+
+/// Index chosen by the query planner
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExplainIndex {
+    /// Design document ID of the index, `None` for `_all_docs`
+    pub ddoc: Option<String>,
+    /// Index name
+    pub name: String,
+    /// Index type, `special` means `_all_docs`
+    #[serde(rename = "type")]
+    pub index_type: String,
+    /// Index definition
+    pub def: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{validate_mango_query, validate_sort, QueryViolation, QueryWarning};
+
+    /// Paths and messages of the violations, in the order they were found
+    fn pairs(violations: Vec<QueryViolation>) -> Vec<(String, String)> {
+        violations
+            .into_iter()
+            .map(|violation| (violation.path, violation.message))
+            .collect()
+    }
+
+    fn check(query: Value) -> Vec<(String, String)> {
+        pairs(validate_mango_query(query.as_object().unwrap()))
+    }
+
+    fn pair(path: &str, message: &str) -> (String, String) {
+        (path.to_string(), message.to_string())
+    }
+
+    #[test]
+    fn valid_query() {
+        let query = json!({
+            "selector": {"year": {"$gt": 2010}},
+            "fields": ["_id", "year"],
+            "sort": [{"year": "asc"}],
+            "limit": 2,
+            "skip": 0,
+            "use_index": ["_design/by_year", "year"],
+            "r": 1,
+            "conflicts": false,
+            "bookmark": "nil",
+            "partition": "sensor-1",
+            "stale": "ok"
+        });
+        assert!(check(query).is_empty());
+    }
+
+    #[test]
+    fn missing_or_invalid_selector() {
+        assert_eq!(
+            check(json!({})),
+            vec![pair("/selector", "missing selector")]
+        );
+        assert_eq!(
+            check(json!({"selector": []})),
+            vec![pair("/selector", "the selector must be a JSON object")]
+        );
+    }
+
+    #[test]
+    fn every_violation_is_reported() {
+        let violations = check(json!({
+            "selector": {},
+            "fields": ["_id", 1],
+            "limit": -1,
+            "r": 0,
+            "stale": "update_after",
+            "colour": "red"
+        }));
+        assert_eq!(
+            violations,
+            vec![
+                pair("/colour", "unknown key"),
+                pair("/fields/1", "expected a string"),
+                pair("/limit", "expected a non negative integer"),
+                pair("/r", "expected a positive integer"),
+                pair("/stale", "expected `ok`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn use_index_length() {
+        assert_eq!(
+            check(json!({"selector": {}, "use_index": ["a", "b", "c"]})),
+            vec![pair(
+                "/use_index",
+                "expected a design document and optionally an index name"
+            )]
+        );
+        assert_eq!(
+            check(json!({"selector": {}, "use_index": "_design/by_year"})),
+            vec![pair("/use_index", "expected an array of strings")]
+        );
+    }
+
+    #[test]
+    fn sort_fields() {
+        assert!(pairs(validate_sort(&json!(["year", {"title": "asc"}]))).is_empty());
+        assert!(pairs(validate_sort(&json!([{"year": "desc"}, {"title": "desc"}]))).is_empty());
+        assert_eq!(
+            pairs(validate_sort(&json!({"year": "asc"}))),
+            vec![pair("/sort", "expected an array")]
+        );
+    }
+
+    #[test]
+    fn invalid_sort_fields() {
+        assert_eq!(
+            pairs(validate_sort(
+                &json!([{"year": "up"}, 1, {"a": "asc", "b": "asc"}])
+            )),
+            vec![
+                pair("/sort/0", "the direction must be `asc` or `desc`"),
+                pair(
+                    "/sort/1",
+                    "expected a field name or an object with a single field and its direction"
+                ),
+                pair(
+                    "/sort/2",
+                    "expected a field name or an object with a single field and its direction"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn mixed_sort_directions() {
+        assert_eq!(
+            pairs(validate_sort(&json!(["year", {"title": "desc"}]))),
+            vec![pair(
+                "/sort/1",
+                "direction `desc` differs from `asc` of the previous fields"
+            )]
+        );
+    }
+
+    #[test]
+    fn no_matching_index_warning() {
+        assert_eq!(
+            QueryWarning::parse("No matching index found, create an index to optimize query time."),
+            vec![QueryWarning::NoMatchingIndex]
+        );
+    }
+
+    #[test]
+    fn too_many_docs_examined_warning() {
+        assert_eq!(
+            QueryWarning::parse("The number of documents examined is high in proportion to the number of results returned. Consider adding a more specific index to improve this."),
+            vec![QueryWarning::TooManyDocsExamined]
+        );
+    }
+
+    #[test]
+    fn index_not_used_warning() {
+        let warning = "_design/by_year, year was not used because it does not contain a valid index for this query.";
+        assert_eq!(
+            QueryWarning::parse(warning),
+            vec![QueryWarning::IndexNotUsed(warning.to_string())]
+        );
+    }
+
+    #[test]
+    fn multiple_warnings() {
+        let warning = "_design/by_year, year was not used because it is not a valid index for this query.\nNo matching index found, create an index to optimize query time.\n";
+        assert_eq!(
+            QueryWarning::parse(warning),
+            vec![
+                QueryWarning::IndexNotUsed(
+                    "_design/by_year, year was not used because it is not a valid index for this query."
+                        .to_string()
+                ),
+                QueryWarning::NoMatchingIndex,
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_and_empty_warnings() {
+        assert_eq!(
+            QueryWarning::parse("  something new  "),
+            vec![QueryWarning::Unknown("something new".to_string())]
+        );
+        assert!(QueryWarning::parse("").is_empty());
+        assert!(QueryWarning::parse("\n \n").is_empty());
+    }
 }
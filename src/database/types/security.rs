@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Security object of a database, defining its admins and members
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SecurityObject {
+    /// Users and roles allowed to manage the database, its design documents and its security object
+    #[serde(default)]
+    pub admins: SecurityGroup,
+    /// Users and roles allowed to read and write documents, when both lists are empty the database is public
+    #[serde(default)]
+    pub members: SecurityGroup,
+    /// Fields returned by CouchDB which are not mapped by this struct
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Users and roles of a security object section
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SecurityGroup {
+    /// User names
+    #[serde(default)]
+    pub names: Vec<String>,
+    /// Role names
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+impl SecurityObject {
+    /// Build a security object adding users and roles one at a time
+    ///
+    /// ## Example
+    /// ```ignore
+    /// let security = SecurityObject::builder()
+    ///                 .admin_role("ops")
+    ///                 .member_name("service_x")
+    ///                 .build();
+    /// ```
+    pub fn builder() -> SecurityObjectBuilder {
+        SecurityObjectBuilder::default()
+    }
+
+    /// Add the users and roles of `other` which are missing, returns whether something was added
+    pub fn merge(&mut self, other: &SecurityObject) -> bool {
+        let admins = self.admins.merge(&other.admins);
+        let members = self.members.merge(&other.members);
+        admins || members
+    }
+
+    /// Remove the users and roles of `other`, returns whether something was removed
+    pub fn remove(&mut self, other: &SecurityObject) -> bool {
+        let admins = self.admins.remove(&other.admins);
+        let members = self.members.remove(&other.members);
+        admins || members
+    }
+
+    /// Whether every user and role of `other` is part of this security object
+    pub fn contains(&self, other: &SecurityObject) -> bool {
+        self.admins.contains(&other.admins) && self.members.contains(&other.members)
+    }
+
+    /// Whether no user and role of `other` is part of this security object
+    pub fn excludes(&self, other: &SecurityObject) -> bool {
+        self.admins.excludes(&other.admins) && self.members.excludes(&other.members)
+    }
+}
+
+impl SecurityGroup {
+    fn merge(&mut self, other: &SecurityGroup) -> bool {
+        let len = self.names.len() + self.roles.len();
+        for name in &other.names {
+            if !self.names.contains(name) {
+                self.names.push(name.clone());
+            }
+        }
+        for role in &other.roles {
+            if !self.roles.contains(role) {
+                self.roles.push(role.clone());
+            }
+        }
+        self.names.len() + self.roles.len() != len
+    }
+
+    fn remove(&mut self, other: &SecurityGroup) -> bool {
+        let len = self.names.len() + self.roles.len();
+        self.names.retain(|name| !other.names.contains(name));
+        self.roles.retain(|role| !other.roles.contains(role));
+        self.names.len() + self.roles.len() != len
+    }
+
+    fn contains(&self, other: &SecurityGroup) -> bool {
+        other.names.iter().all(|name| self.names.contains(name))
+            && other.roles.iter().all(|role| self.roles.contains(role))
+    }
+
+    fn excludes(&self, other: &SecurityGroup) -> bool {
+        !other.names.iter().any(|name| self.names.contains(name))
+            && !other.roles.iter().any(|role| self.roles.contains(role))
+    }
+}
+
+/// Builder of a [SecurityObject], created by [SecurityObject::builder]
+#[derive(Debug, Clone, Default)]
+pub struct SecurityObjectBuilder {
+    security: SecurityObject,
+}
+
+impl SecurityObjectBuilder {
+    /// Add a user allowed to manage the database
+    pub fn admin_name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.security.admins.names.push(name.into());
+        self
+    }
+
+    /// Add a role allowed to manage the database
+    pub fn admin_role<S>(mut self, role: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.security.admins.roles.push(role.into());
+        self
+    }
+
+    /// Add a user allowed to read and write documents
+    pub fn member_name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.security.members.names.push(name.into());
+        self
+    }
+
+    /// Add a role allowed to read and write documents
+    pub fn member_role<S>(mut self, role: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.security.members.roles.push(role.into());
+        self
+    }
+
+    pub fn build(self) -> SecurityObject {
+        self.security
+    }
+}
@@ -0,0 +1,32 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Shards of a database and the nodes holding a copy of each of them
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShardsResponse {
+    /// Nodes holding each shard, keyed by the shard range, e.g. `00000000-7fffffff`
+    pub shards: BTreeMap<String, Vec<String>>,
+}
+
+impl ShardsResponse {
+    /// Shard ranges held by each node
+    pub fn by_node(&self) -> BTreeMap<String, Vec<String>> {
+        let mut nodes = BTreeMap::<String, Vec<String>>::new();
+        for (range, holders) in &self.shards {
+            for node in holders {
+                nodes.entry(node.clone()).or_default().push(range.clone());
+            }
+        }
+        nodes
+    }
+}
+
+/// Shard a document belongs to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocShard {
+    /// Range of the shard, e.g. `00000000-7fffffff`
+    pub range: String,
+    /// Nodes holding a copy of the shard
+    pub nodes: Vec<String>,
+}
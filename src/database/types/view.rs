@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Result of a view query, `K` is the type of the emitted keys and `V` the type of the emitted or reduced values
+///
+/// ## Example reading a `_stats` reduced view
+/// ```ignore
+/// let stats: ViewResponse<Value, StatsReduce> = my_db.view("orders", "totals", Some(&params)).await?;
+/// println!("average {}", stats.rows[0].value.sum / stats.rows[0].value.count as f64);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ViewResponse<K = Value, V = Value> {
+    /// Number of rows in the view, not present on reduced results
+    pub total_rows: Option<i64>,
+    /// Offset where the row list started, not present on reduced results
+    pub offset: Option<i64>,
+    /// Vector of view rows
+    pub rows: Vec<ViewRow<K, V>>,
+    /// Current update sequence of the database, present if `update_seq=true` is provided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_seq: Option<Value>,
+}
+
+/// Single row of a view result
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ViewRow<K = Value, V = Value> {
+    /// ID of the document which emitted the row, not present on reduced results
+    pub id: Option<String>,
+    /// Emitted key, `null` when the whole view is reduced to a single row
+    pub key: K,
+    /// Emitted or reduced value
+    pub value: V,
+    /// Document body, present if `include_docs=true` is provided
+    pub doc: Option<Value>,
+}
+
+/// Value computed by the `_stats` built-in reduce function
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatsReduce {
+    /// Sum of the emitted values
+    pub sum: f64,
+    /// Number of emitted values
+    pub count: i64,
+    /// Smallest emitted value
+    pub min: f64,
+    /// Biggest emitted value
+    pub max: f64,
+    /// Sum of the squares of the emitted values
+    pub sumsqr: f64,
+}
@@ -1,7 +1,13 @@
-use serde::{Deserialize, Serialize};
+use bytes::Bytes;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
 use thiserror::Error;
 
+use crate::database::types::{BulkDocError, BulkDocsRes};
+use crate::Feature;
+
 /// Nano Error
 #[derive(Error, Debug)]
 pub enum NanoError {
@@ -17,6 +23,149 @@ pub enum NanoError {
     /// Generic CouchDB errors which does not include statusc code
     #[error("{0}")]
     GenericCouchdbError(Value),
+    /// I/O errors when reading or writing files and streams
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    /// Every node of a node pool failed its last health check
+    #[error("No healthy CouchDB node available")]
+    NoHealthyNode,
+    /// The CouchDB node does not have the feature required by the API
+    #[error("The CouchDB node does not support the `{0}` feature")]
+    Unsupported(Feature),
+    /// The digest of the received content differs from the one sent by CouchDB
+    #[error("Digest mismatch, expected: {expected}, received: {received}")]
+    DigestMismatch { expected: String, received: String },
+    /// The operation did not complete in the given time
+    #[error("Operation timed out after {0:?}")]
+    Timeout(std::time::Duration),
+    /// The field is not part of the type it should belong to
+    #[error("Field `{field}` is not a field of `{type_name}`")]
+    UnknownField { field: String, type_name: String },
+    /// The document ID is not a valid partitioned ID
+    #[error("Invalid partitioned document ID: {0}")]
+    InvalidPartitionedId(String),
+    /// The response body does not match the expected type
+    #[error("Unable to parse the response of {endpoint} as `{type_name}`: {source}, body: {body}")]
+    UnexpectedResponse {
+        /// Url of the request, without credentials
+        endpoint: String,
+        /// Type the body was parsed into
+        type_name: &'static str,
+        /// Received body, truncated
+        body: String,
+        source: serde_json::Error,
+    },
+    /// The database matches a protected pattern and can be deleted only with `force_delete_db`
+    #[error("The database `{0}` is protected, use force_delete_db to delete it")]
+    ProtectedDatabase(String),
+    /// The document doesn't match the JSON Schema of its [TypedDb](crate::database::typed::TypedDb)
+    #[error("Document does not match its schema: {}", .0.iter().map(|violation| violation.to_string()).collect::<Vec<_>>().join(", "))]
+    SchemaViolation(Vec<SchemaViolation>),
+    /// The document has no ID or revision because it was never saved
+    #[error("The document has not been saved yet")]
+    UnsavedDocument,
+    /// The JSON Schema can't be compiled
+    #[error("Invalid JSON Schema: {0}")]
+    InvalidSchema(String),
+    /// `_bulk_docs` answered `417 Expectation Failed`, documents were rejected and the request was not applied
+    #[error("Bulk write rejected: {}", .0.iter().filter_map(|res| res.failure().map(|error| format!("{}: {}", res.id, error))).collect::<Vec<_>>().join(", "))]
+    BulkDocsRejected(Vec<BulkDocsRes>),
+    /// A document was not written, e.g. rejected by a `validate_doc_update` function
+    #[error("Document `{id}` not written, {error}")]
+    DocRejected { id: String, error: BulkDocError },
+    /// The JSON of a Mango query is not valid, see [MangoQuery::from_json](crate::database::types::MangoQuery::from_json)
+    #[error("Invalid Mango query: {}", .0.iter().map(|violation| violation.to_string()).collect::<Vec<_>>().join(", "))]
+    InvalidQuery(Vec<QueryViolation>),
+    /// The response body is not JSON, e.g. the HTML page of a reverse proxy answering in place of CouchDB
+    #[error("Status Code: {status}, non JSON response: {body}")]
+    NonJsonResponse {
+        /// Response status code
+        status: u16,
+        /// Received body, truncated
+        body: String,
+    },
+    /// Concurrent writers kept replacing the security object, the grant or revoke was not applied
+    #[error("Security object update not applied after {0} attempts")]
+    SecurityUpdateConflict(usize),
+}
+
+impl NanoError {
+    /// Name of the variant, in snake case
+    fn kind(&self) -> &'static str {
+        match self {
+            NanoError::InvalidRequest(_) => "invalid_request",
+            NanoError::GenericCouchdbErrorWithCode(_) => "generic_couchdb_error_with_code",
+            NanoError::InvalidJson(_) => "invalid_json",
+            NanoError::GenericCouchdbError(_) => "generic_couchdb_error",
+            NanoError::IoError(_) => "io_error",
+            NanoError::NoHealthyNode => "no_healthy_node",
+            NanoError::Unsupported(_) => "unsupported",
+            NanoError::DigestMismatch { .. } => "digest_mismatch",
+            NanoError::Timeout(_) => "timeout",
+            NanoError::UnknownField { .. } => "unknown_field",
+            NanoError::InvalidPartitionedId(_) => "invalid_partitioned_id",
+            NanoError::UnexpectedResponse { .. } => "unexpected_response",
+            NanoError::ProtectedDatabase(_) => "protected_database",
+            NanoError::SchemaViolation(_) => "schema_violation",
+            NanoError::UnsavedDocument => "unsaved_document",
+            NanoError::InvalidSchema(_) => "invalid_schema",
+            NanoError::BulkDocsRejected(_) => "bulk_docs_rejected",
+            NanoError::DocRejected { .. } => "doc_rejected",
+            NanoError::InvalidQuery(_) => "invalid_query",
+            NanoError::NonJsonResponse { .. } => "non_json_response",
+            NanoError::SecurityUpdateConflict(_) => "security_update_conflict",
+        }
+    }
+}
+
+/// Serialized as `{"error": ..., "reason": ...}`, CouchDB errors keep their own `error`, `reason` and `status_code`
+/// while the other errors use the name of their variant and their message
+impl Serialize for NanoError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if let NanoError::GenericCouchdbErrorWithCode(error) = self {
+            return error.serialize(serializer);
+        }
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("error", self.kind())?;
+        map.serialize_entry("reason", &self.to_string())?;
+        if let NanoError::NonJsonResponse { status, .. } = self {
+            map.serialize_entry("status_code", status)?;
+        }
+        map.end()
+    }
+}
+
+/// Part of a document not matching its JSON Schema
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// JSON Pointer to the invalid value, empty for the whole document
+    pub path: String,
+    /// Description of the violated constraint
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Part of a Mango query that is not valid
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QueryViolation {
+    /// JSON Pointer to the invalid value, empty for the whole query
+    pub path: String,
+    /// What is wrong with the value
+    pub message: String,
+}
+
+impl std::fmt::Display for QueryViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
 }
 
 /// CouchDB HTTP Error
@@ -30,3 +179,124 @@ pub struct CouchDBError {
     #[serde(default)]
     pub status_code: u16,
 }
+
+impl_display_json!(CouchDBError);
+
+/// Number of characters of a response body kept in [NanoError::UnexpectedResponse]
+const UNEXPECTED_BODY_LEN: usize = 500;
+
+/// Parse a response body, on failure the error includes the endpoint and the body
+pub(crate) fn parse_response<T>(endpoint: &str, body: Value) -> Result<T, NanoError>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(&body).map_err(|source| NanoError::UnexpectedResponse {
+        endpoint: redact_url(endpoint),
+        type_name: std::any::type_name::<T>(),
+        body: truncate(body.to_string(), UNEXPECTED_BODY_LEN),
+        source,
+    })
+}
+
+/// Parse a raw JSON body, on failure the error includes the endpoint and the body
+pub(crate) fn parse_slice<T>(endpoint: &str, body: &[u8]) -> Result<T, NanoError>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_slice(body).map_err(|source| NanoError::UnexpectedResponse {
+        endpoint: redact_url(endpoint),
+        type_name: std::any::type_name::<T>(),
+        body: truncate(
+            String::from_utf8_lossy(body).into_owned(),
+            UNEXPECTED_BODY_LEN,
+        ),
+        source,
+    })
+}
+
+/// Read a response body straight into `T` on a 2xx status, without an intermediate [Value], otherwise
+/// into a [NanoError::GenericCouchdbErrorWithCode]
+pub(crate) async fn read_response<T>(
+    endpoint: &str,
+    response: reqwest::Response,
+) -> Result<T, NanoError>
+where
+    T: DeserializeOwned,
+{
+    let status = response.status().as_u16();
+    let body = read_bytes(endpoint, response).await?;
+    parse_slice(endpoint, &body).map_err(|err| match serde_json::from_slice::<IgnoredAny>(&body) {
+        Ok(_) => err,
+        Err(_) => non_json_response(status, &body),
+    })
+}
+
+/// Read the raw body of a response on a 2xx status, otherwise the body is parsed into a
+/// [NanoError::GenericCouchdbErrorWithCode]
+pub(crate) async fn read_bytes(
+    endpoint: &str,
+    response: reqwest::Response,
+) -> Result<Bytes, NanoError> {
+    let status = response.status();
+    let body = response.bytes().await?;
+    if status.is_success() {
+        return Ok(body);
+    }
+    let body: Value =
+        serde_json::from_slice(&body).map_err(|_| non_json_response(status.as_u16(), &body))?;
+    Err(NanoError::GenericCouchdbErrorWithCode(CouchDBError {
+        status_code: status.as_u16(),
+        ..parse_response(endpoint, body)?
+    }))
+}
+
+/// Read a response body as JSON, a body which isn't JSON gives [NanoError::NonJsonResponse]
+pub(crate) async fn read_json(response: reqwest::Response) -> Result<Value, NanoError> {
+    let status = response.status().as_u16();
+    let body = response.bytes().await?;
+    serde_json::from_slice(&body).map_err(|_| non_json_response(status, &body))
+}
+
+/// Error for a response body which isn't JSON
+pub(crate) fn non_json_response(status: u16, body: &[u8]) -> NanoError {
+    NanoError::NonJsonResponse {
+        status,
+        body: truncate(
+            String::from_utf8_lossy(body).into_owned(),
+            UNEXPECTED_BODY_LEN,
+        ),
+    }
+}
+
+/// Remove the credentials from an url, also when it can't be parsed
+pub(crate) fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut url) => {
+            let _ = url.set_username("");
+            let _ = url.set_password(None);
+            url.to_string()
+        }
+        // drop any `userinfo@` between the scheme and the path by hand
+        Err(_) => {
+            let Some(scheme_end) = url.find("://").map(|index| index + 3) else {
+                return url.to_string();
+            };
+            let authority_end = url[scheme_end..]
+                .find('/')
+                .map_or(url.len(), |index| scheme_end + index);
+            match url[scheme_end..authority_end].rfind('@') {
+                Some(index) => format!("{}{}", &url[..scheme_end], &url[scheme_end + index + 1..]),
+                None => url.to_string(),
+            }
+        }
+    }
+}
+
+/// Keep the first `max_chars` characters of a text
+pub(crate) fn truncate(mut text: String, max_chars: usize) -> String {
+    if let Some((index, _)) = text.char_indices().nth(max_chars) {
+        text.truncate(index);
+        text.push_str("...");
+    }
+    text
+}
@@ -0,0 +1,103 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::database::types::ExecutionStats;
+
+/// Number of slowest queries kept by default
+pub const DEFAULT_SLOWEST_QUERIES: usize = 10;
+
+/// Execution statistics of the `_find` queries sent by a client, collected when enabled with
+/// [NanoBuilder::query_stats](crate::NanoBuilder::query_stats) and returned by [Nano::query_stats](crate::Nano::query_stats)
+///
+/// A high `total_docs_examined` compared to `results_returned` means the queries scan documents
+/// which don't match them, usually because of a missing index.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QueryStats {
+    /// Number of queries which returned execution statistics
+    pub queries: u64,
+    /// Number of index keys examined by all the queries
+    pub total_keys_examined: i64,
+    /// Number of documents fetched from the database or index by all the queries
+    pub total_docs_examined: i64,
+    /// Number of documents fetched with a quorum read by all the queries
+    pub total_quorum_docs_examined: i64,
+    /// Number of results returned by all the queries
+    pub results_returned: i64,
+    /// Time spent by CouchDB running all the queries
+    pub execution_time_ms: f64,
+    /// Slowest queries, the slowest first
+    pub slowest: Vec<SlowQuery>,
+}
+
+/// A query kept among the slowest ones
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SlowQuery {
+    /// Name of the database the query was run on
+    pub db_name: String,
+    /// Body of the `_find` request
+    pub query: Value,
+    /// Statistics returned by CouchDB
+    pub execution_stats: ExecutionStats,
+}
+
+/// Accumulates the execution statistics of the queries, shared by a node and its connected databases
+#[derive(Debug)]
+pub(crate) struct QueryStatsCollector {
+    /// Number of slowest queries kept
+    max_slowest: usize,
+    stats: Mutex<QueryStats>,
+}
+
+impl QueryStatsCollector {
+    pub(crate) fn new(max_slowest: usize) -> Self {
+        Self {
+            max_slowest,
+            stats: Mutex::new(QueryStats::default()),
+        }
+    }
+
+    /// Add the statistics of a query
+    pub(crate) fn record(&self, db_name: &str, query: &Value, execution_stats: &ExecutionStats) {
+        let mut stats = self.stats.lock().unwrap_or_else(|err| err.into_inner());
+        stats.queries += 1;
+        stats.total_keys_examined += execution_stats.total_keys_examined;
+        stats.total_docs_examined += execution_stats.total_docs_examined;
+        stats.total_quorum_docs_examined += execution_stats.total_quorum_docs_examined;
+        stats.results_returned += execution_stats.results_returned;
+        stats.execution_time_ms += execution_stats.execution_time_ms;
+
+        let position = stats
+            .slowest
+            .iter()
+            .position(|slow| {
+                slow.execution_stats.execution_time_ms < execution_stats.execution_time_ms
+            })
+            .unwrap_or(stats.slowest.len());
+        if position < self.max_slowest {
+            stats.slowest.insert(
+                position,
+                SlowQuery {
+                    db_name: db_name.to_string(),
+                    query: query.clone(),
+                    execution_stats: execution_stats.clone(),
+                },
+            );
+            stats.slowest.truncate(self.max_slowest);
+        }
+    }
+
+    /// Statistics collected until now
+    pub(crate) fn snapshot(&self) -> QueryStats {
+        self.stats
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Forget the statistics collected until now
+    pub(crate) fn reset(&self) {
+        *self.stats.lock().unwrap_or_else(|err| err.into_inner()) = QueryStats::default();
+    }
+}